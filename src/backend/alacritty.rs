@@ -1,5 +1,5 @@
 use super::Functions;
-use crate::config::Config;
+use crate::config::{Colors, Config, Cursor, CursorShape, KeyAction, KeyBinding, KeyChord, Window};
 use crate::error::GlrnvimError;
 use std::fs;
 use std::path::Path;
@@ -12,6 +12,26 @@ use toml_edit::{value, DocumentMut, Item, Table, Value};
 
 pub const ALACRITTY_NAME: &str = "alacritty";
 
+// The default shortcuts of alacritty outside of the vi mode. Alacritty cannot clear them, so they
+// are bound to ReceiveChar when passing through all keys.
+const DEFAULT_SHORTCUTS: &[&str] = &[
+    "ctrl+shift+c",
+    "ctrl+shift+v",
+    "ctrl+shift+b",
+    "ctrl+shift+f",
+    "ctrl+shift+space",
+    "ctrl+0",
+    "ctrl+equal",
+    "ctrl+plus",
+    "ctrl+minus",
+    "ctrl+l",
+    "shift+insert",
+    "shift+page_up",
+    "shift+page_down",
+    "shift+home",
+    "shift+end",
+];
+
 struct Alacritty {
     exe_path: PathBuf,
     cfg_file: Option<NamedTempFile>,
@@ -52,27 +72,68 @@ impl Alacritty {
             .unwrap()
             .as_table_mut()
             .unwrap();
-        if config.font_size > 0 {
-            font_mapping.insert("size", value(Into::<i64>::into(config.font_size)));
+        if config.font_size > 0.0 {
+            font_mapping.insert("size", value(config.font_size));
         }
         // Set the font
-        if !config.fonts.is_empty() {
-            let mut normal_mapping = Table::new();
-            normal_mapping.insert("family", value(config.fonts.first().unwrap().to_string()));
+        let mut normal_mapping = Table::new();
+        if let Some(family) = config.fonts.first() {
+            normal_mapping.insert("family", value(family.to_string()));
+        }
+        if let Some(style) = config.font.normal_style() {
+            normal_mapping.insert("style", value(style));
+        }
+        if !normal_mapping.is_empty() {
             font_mapping.insert("normal", Item::Table(normal_mapping));
         }
+        // Alacritty uses the normal family for the faces without a family.
+        // OpenType features are not supported by alacritty.
+        for (key, face) in config.font.faces() {
+            let Some(face) = face else { continue };
+            let mut face_mapping = Table::new();
+            if let Some(family) = face.family.as_ref() {
+                face_mapping.insert("family", value(family.to_string()));
+            }
+            if let Some(style) = face.style.as_ref() {
+                face_mapping.insert("style", value(style.to_string()));
+            }
+            if !face_mapping.is_empty() {
+                font_mapping.insert(key, Item::Table(face_mapping));
+            }
+        }
+        if config.line_height.is_some() || config.letter_spacing.is_some() {
+            let mut offset = toml_edit::InlineTable::new();
+            let x = config.letter_spacing.unwrap_or(0);
+            let y = config.line_height.unwrap_or(0);
+            offset.insert("x", Value::from(i64::from(x)));
+            offset.insert("y", Value::from(i64::from(y)));
+            font_mapping.insert("offset", value(offset));
+        }
         // Only overwrite the font setting if it has been set in the glrnvim config
         if font_mapping.is_empty() {
             base_mapping.remove(key_font);
         }
 
-        // Disable some improper key bindings for nvim
-        let mut binding_z = toml_edit::InlineTable::new();
-        binding_z.insert("key", Value::from("Z"));
-        binding_z.insert("mods", Value::from("Control"));
-        binding_z.insert("action", Value::from("None"));
+        Alacritty::set_colors(base_mapping, &config.colors);
+        Alacritty::set_window(base_mapping, &config.window);
+        Alacritty::set_cursor(base_mapping, &config.cursor);
+
+        let mut key_bindings = config.key_bindings();
+        if config.passthrough_all_keys {
+            for shortcut in DEFAULT_SHORTCUTS {
+                let key: KeyChord = shortcut.parse().unwrap();
+                if !key_bindings.iter().any(|b| b.key == key) {
+                    key_bindings.push(KeyBinding {
+                        key,
+                        action: KeyAction::Passthrough,
+                    });
+                }
+            }
+        }
         let mut bindings = toml_edit::Array::new();
-        bindings.push(binding_z);
+        for binding in key_bindings {
+            bindings.push(Alacritty::key_binding(&binding));
+        }
         let mut keyboard = Table::new();
         keyboard["bindings"] = toml_edit::Item::Value(Value::Array(bindings));
         base_mapping["keyboard"] = Item::Table(keyboard);
@@ -86,37 +147,147 @@ impl Alacritty {
         self.cfg_file = Some(file);
     }
 
-    // Load the default alacritty config
-    fn load_alacritty_conf(path: Option<String>) -> DocumentMut {
-        let conf_path = path.or({
-            let base_confs: [String; 0] = [];
-            let pri_confs: [String; 3] = [
-                "$XDG_CONFIG_HOME/alacritty/alacritty.toml".to_string(),
-                "$HOME/.config/alacritty/alacritty.toml".to_string(),
-                "$XDG_CONFIG_DIRS/alacritty/alacritty.toml".to_string(),
-            ];
-            let confs = super::find_term_conf_files(&base_confs, &pri_confs);
-            if confs.is_empty() {
-                None
-            } else {
-                Some(confs[0].to_string())
+    fn key_binding(binding: &KeyBinding) -> toml_edit::InlineTable {
+        let key = match binding.key.key.as_str() {
+            "tab" => "Tab".to_string(),
+            "enter" => "Enter".to_string(),
+            "escape" => "Escape".to_string(),
+            "space" => "Space".to_string(),
+            "backspace" => "Back".to_string(),
+            "delete" => "Delete".to_string(),
+            "insert" => "Insert".to_string(),
+            "home" => "Home".to_string(),
+            "end" => "End".to_string(),
+            "page_up" => "PageUp".to_string(),
+            "page_down" => "PageDown".to_string(),
+            "up" => "Up".to_string(),
+            "down" => "Down".to_string(),
+            "left" => "Left".to_string(),
+            "right" => "Right".to_string(),
+            "plus" => "+".to_string(),
+            "minus" => "-".to_string(),
+            "equal" => "=".to_string(),
+            k => k.to_uppercase(),
+        };
+        let action = match binding.action {
+            KeyAction::Unbind => "None",
+            KeyAction::Passthrough => "ReceiveChar",
+            // Sent as chars instead of an action.
+            KeyAction::CsiU => "",
+            KeyAction::Copy => "Copy",
+            KeyAction::Paste => "Paste",
+            KeyAction::IncreaseFontSize => "IncreaseFontSize",
+            KeyAction::DecreaseFontSize => "DecreaseFontSize",
+            KeyAction::ResetFontSize => "ResetFontSize",
+            KeyAction::ToggleFullscreen => "ToggleFullscreen",
+        };
+        let mods = super::key_mods(&binding.key, ["Control", "Shift", "Alt", "Super"]);
+
+        let mut table = toml_edit::InlineTable::new();
+        table.insert("key", Value::from(key));
+        if !mods.is_empty() {
+            table.insert("mods", Value::from(mods.join("|")));
+        }
+        if binding.action == KeyAction::CsiU {
+            let chars = format!("\x1b[{}", super::csi_u(&binding.key));
+            table.insert("chars", Value::from(chars));
+        } else {
+            table.insert("action", Value::from(action));
+        }
+        table
+    }
+
+    fn set_colors(base_mapping: &mut DocumentMut, colors: &Colors) {
+        if colors.is_empty() {
+            return;
+        }
+        let colors_mapping = super::toml_table(base_mapping, "colors");
+        let mut set = |table: &str, key: &str, color: &Option<crate::config::Color>| {
+            if let Some(c) = color {
+                super::toml_table(colors_mapping, table).insert(key, value(c.to_string()));
             }
-        });
-        match conf_path {
-            Some(p) => {
-                let content = std::fs::read_to_string(p.clone())
-                    .expect(format!("Cannot load term config file: '{}'", p).as_ref());
-                match content.parse::<DocumentMut>() {
-                    Ok(mapping) => mapping,
-                    Err(msg) => {
-                        log::warn!("Cannot identify executable name from '{}'", msg);
-                        DocumentMut::new()
-                    }
-                }
+        };
+        set("primary", "foreground", &colors.foreground);
+        set("primary", "background", &colors.background);
+        set("cursor", "cursor", &colors.cursor);
+        set("selection", "text", &colors.selection_foreground);
+        set("selection", "background", &colors.selection_background);
+        for (i, c) in colors.palette.iter().enumerate() {
+            let table = if i < 8 { "normal" } else { "bright" };
+            set(table, super::ANSI_COLOR_NAMES[i % 8], &Some(*c));
+        }
+    }
+
+    fn set_window(base_mapping: &mut DocumentMut, window: &Window) {
+        if *window == Window::default() {
+            return;
+        }
+        let window_mapping = super::toml_table(base_mapping, "window");
+        if let Some((columns, lines)) = window.size() {
+            let mut dimensions = toml_edit::InlineTable::new();
+            dimensions.insert("columns", Value::from(i64::from(columns)));
+            dimensions.insert("lines", Value::from(i64::from(lines)));
+            window_mapping.insert("dimensions", value(dimensions));
+        }
+        if let Some(p) = window.position {
+            let mut position = toml_edit::InlineTable::new();
+            position.insert("x", Value::from(i64::from(p.x)));
+            position.insert("y", Value::from(i64::from(p.y)));
+            window_mapping.insert("position", value(position));
+        }
+        if window.fullscreen {
+            window_mapping.insert("startup_mode", value("Fullscreen"));
+        } else if window.maximized {
+            window_mapping.insert("startup_mode", value("Maximized"));
+        }
+        if let Some(decorations) = window.decorations {
+            let decorations = if decorations { "Full" } else { "None" };
+            window_mapping.insert("decorations", value(decorations));
+        }
+        if let Some(p) = window.padding {
+            let mut padding = toml_edit::InlineTable::new();
+            padding.insert("x", Value::from(i64::from(p.x)));
+            padding.insert("y", Value::from(i64::from(p.y)));
+            window_mapping.insert("padding", value(padding));
+        }
+        if let Some(opacity) = window.opacity {
+            window_mapping.insert("opacity", value(f64::from(opacity)));
+        }
+    }
+
+    fn set_cursor(base_mapping: &mut DocumentMut, cursor: &Cursor) {
+        if !cursor.has_style() {
+            return;
+        }
+        let cursor_mapping = super::toml_table(base_mapping, "cursor");
+        if cursor.shape.is_some() || cursor.blink.is_some() {
+            let style = super::toml_table(cursor_mapping, "style");
+            if let Some(shape) = cursor.shape {
+                let shape = match shape {
+                    CursorShape::Block => "Block",
+                    CursorShape::Beam => "Beam",
+                    CursorShape::Underline => "Underline",
+                };
+                style.insert("shape", value(shape));
+            }
+            if let Some(blink) = cursor.blink {
+                style.insert("blinking", value(if blink { "On" } else { "Off" }));
             }
-            _ => DocumentMut::new(),
+        }
+        if let Some(interval) = cursor.blink_interval {
+            cursor_mapping.insert("blink_interval", value(i64::from(interval)));
         }
     }
+
+    // Load the default alacritty config
+    fn load_alacritty_conf(path: Option<String>) -> DocumentMut {
+        let pri_confs: [String; 3] = [
+            "$XDG_CONFIG_HOME/alacritty/alacritty.toml".to_string(),
+            "$HOME/.config/alacritty/alacritty.toml".to_string(),
+            "$XDG_CONFIG_DIRS/alacritty/alacritty.toml".to_string(),
+        ];
+        super::load_toml_conf(path, &pri_confs)
+    }
 }
 
 impl Functions for Alacritty {
@@ -137,7 +308,7 @@ impl Functions for Alacritty {
 
         command.arg("-e");
         command.arg(&config.nvim_exe_path);
-        command.args(super::COMMON_ARGS);
+        command.args(super::common_args(config));
 
         command
     }
@@ -163,14 +334,9 @@ impl Functions for Alacritty {
             count += 1;
             let s = System::new_all();
             for process in s.processes_by_name(proc_name) {
-                match process.parent() {
-                    None => {}
-                    Some(ppid) => {
-                        if ppid == term_pid {
-                            process.kill_with(Signal::Winch);
-                            return;
-                        }
-                    }
+                if process.parent() == Some(term_pid) {
+                    process.kill_with(Signal::Winch);
+                    return;
                 }
             }
             if count == 10 {
@@ -194,8 +360,8 @@ mod tests {
     fn test_create_basic_alacritty_conf() {
         let conf = config::Config {
             backend: Some(config::Backend::Alacritty),
-            font_size: 14,
-            fonts: vec!["test_font".to_string()],
+            font_size: 14.0,
+            fonts: vec!["test_font".into()],
             ..Default::default()
         };
         let mut alacritty = Alacritty {
@@ -208,13 +374,13 @@ mod tests {
         let result = fs::read_to_string(tmp_conf.as_ref().unwrap().path());
         assert!(result.is_ok());
         let expected = r#"[font]
-size = 14
+size = 14.0
 
 [font.normal]
 family = "test_font"
 
 [keyboard]
-bindings = [{ key = "Z", mods = "Control", action = "None" }]
+bindings = [{ key = "Z", mods = "Control", action = "None" }, { key = "=", mods = "Control", action = "IncreaseFontSize" }, { key = "+", mods = "Control", action = "IncreaseFontSize" }, { key = "-", mods = "Control", action = "DecreaseFontSize" }, { key = "0", mods = "Control", action = "ResetFontSize" }]
 "#;
         assert_eq!(result.unwrap_or_default(), expected)
     }
@@ -228,8 +394,8 @@ bindings = [{ key = "Z", mods = "Control", action = "None" }]
 
         let conf = config::Config {
             backend: Some(config::Backend::Alacritty),
-            font_size: 14,
-            fonts: vec!["test_font".to_string()],
+            font_size: 14.0,
+            fonts: vec!["test_font".into()],
             ..Default::default()
         };
         let mut alacritty = Alacritty {
@@ -242,13 +408,13 @@ bindings = [{ key = "Z", mods = "Control", action = "None" }]
         let result = fs::read_to_string(tmp_conf.as_ref().unwrap().path());
         assert!(result.is_ok());
         let expected = r#"[font]
-size = 14
+size = 14.0
 
 [font.normal]
 family = "test_font"
 
 [keyboard]
-bindings = [{ key = "Z", mods = "Control", action = "None" }]
+bindings = [{ key = "Z", mods = "Control", action = "None" }, { key = "=", mods = "Control", action = "IncreaseFontSize" }, { key = "+", mods = "Control", action = "IncreaseFontSize" }, { key = "-", mods = "Control", action = "DecreaseFontSize" }, { key = "0", mods = "Control", action = "ResetFontSize" }]
 "#;
         assert_eq!(result.unwrap_or_default(), expected)
     }
@@ -260,8 +426,10 @@ bindings = [{ key = "Z", mods = "Control", action = "None" }]
         font_mapping.insert("size", value(42));
         term_conf.insert("font", Item::Table(font_mapping));
 
-        let mut conf = config::Config::default();
-        conf.backend = Some(config::Backend::Alacritty);
+        let conf = config::Config {
+            backend: Some(config::Backend::Alacritty),
+            ..Default::default()
+        };
         let mut alacritty = Alacritty {
             exe_path: PathBuf::new(),
             cfg_file: None,
@@ -275,7 +443,7 @@ bindings = [{ key = "Z", mods = "Control", action = "None" }]
 size = 42
 
 [keyboard]
-bindings = [{ key = "Z", mods = "Control", action = "None" }]
+bindings = [{ key = "Z", mods = "Control", action = "None" }, { key = "=", mods = "Control", action = "IncreaseFontSize" }, { key = "+", mods = "Control", action = "IncreaseFontSize" }, { key = "-", mods = "Control", action = "DecreaseFontSize" }, { key = "0", mods = "Control", action = "ResetFontSize" }]
 "#;
         assert_eq!(result.unwrap_or_default(), expected)
     }
@@ -308,7 +476,245 @@ bindings = [{ key = "Z", mods = "Control", action = "None" }]
 background = "0x424242"
 
 [keyboard]
-bindings = [{ key = "Z", mods = "Control", action = "None" }]
+bindings = [{ key = "Z", mods = "Control", action = "None" }, { key = "=", mods = "Control", action = "IncreaseFontSize" }, { key = "+", mods = "Control", action = "IncreaseFontSize" }, { key = "-", mods = "Control", action = "DecreaseFontSize" }, { key = "0", mods = "Control", action = "ResetFontSize" }]
+"#;
+        assert_eq!(result.unwrap_or_default(), expected)
+    }
+
+    #[test]
+    fn test_alacritty_colors() {
+        let mut term_conf = DocumentMut::new();
+        let mut primary = Table::new();
+        let mut colors = Table::new();
+        primary.insert("background", value("0x424242".to_string()));
+        primary.insert("dim_foreground", value("0x828482".to_string()));
+        colors.insert("primary", Item::Table(primary));
+        term_conf.insert("colors", Item::Table(colors));
+
+        let mut palette = vec!["#000000".parse().unwrap(); 16];
+        palette[1] = "#cc6666".parse().unwrap();
+        palette[9] = "#d54e53".parse().unwrap();
+        let conf = config::Config {
+            backend: Some(config::Backend::Alacritty),
+            colors: config::Colors {
+                background: Some("#1d1f21".parse().unwrap()),
+                cursor: Some("#aabbcc".parse().unwrap()),
+                palette,
+                ..Default::default()
+            },
+            ..Default::default()
+        };
+        let mut alacritty = Alacritty {
+            exe_path: PathBuf::new(),
+            cfg_file: None,
+        };
+        alacritty.create_conf_file(&mut term_conf, &conf);
+        let tmp_conf = alacritty.cfg_file;
+        let result = fs::read_to_string(tmp_conf.as_ref().unwrap().path());
+        let expected = r##"[colors]
+
+[colors.primary]
+background = "#1d1f21"
+dim_foreground = "0x828482"
+
+[colors.cursor]
+cursor = "#aabbcc"
+
+[colors.normal]
+black = "#000000"
+red = "#cc6666"
+green = "#000000"
+yellow = "#000000"
+blue = "#000000"
+magenta = "#000000"
+cyan = "#000000"
+white = "#000000"
+
+[colors.bright]
+black = "#000000"
+red = "#d54e53"
+green = "#000000"
+yellow = "#000000"
+blue = "#000000"
+magenta = "#000000"
+cyan = "#000000"
+white = "#000000"
+
+[keyboard]
+bindings = [{ key = "Z", mods = "Control", action = "None" }, { key = "=", mods = "Control", action = "IncreaseFontSize" }, { key = "+", mods = "Control", action = "IncreaseFontSize" }, { key = "-", mods = "Control", action = "DecreaseFontSize" }, { key = "0", mods = "Control", action = "ResetFontSize" }]
+"##;
+        assert_eq!(result.unwrap_or_default(), expected)
+    }
+
+    #[test]
+    fn test_alacritty_window() {
+        let mut term_conf = DocumentMut::new();
+        let mut window = Table::new();
+        window.insert("opacity", value(0.9));
+        term_conf.insert("window", Item::Table(window));
+
+        let conf = config::Config {
+            backend: Some(config::Backend::Alacritty),
+            window: config::Window {
+                columns: Some(100),
+                lines: Some(40),
+                maximized: true,
+                position: Some(config::Position { x: 10, y: 20 }),
+                decorations: Some(false),
+                padding: Some(config::Padding { x: 5, y: 10 }),
+                ..Default::default()
+            },
+            ..Default::default()
+        };
+        let mut alacritty = Alacritty {
+            exe_path: PathBuf::new(),
+            cfg_file: None,
+        };
+        alacritty.create_conf_file(&mut term_conf, &conf);
+        let tmp_conf = alacritty.cfg_file;
+        let result = fs::read_to_string(tmp_conf.as_ref().unwrap().path());
+        let expected = r#"[window]
+opacity = 0.9
+dimensions = { columns = 100, lines = 40 }
+position = { x = 10, y = 20 }
+startup_mode = "Maximized"
+decorations = "None"
+padding = { x = 5, y = 10 }
+
+[keyboard]
+bindings = [{ key = "Z", mods = "Control", action = "None" }, { key = "=", mods = "Control", action = "IncreaseFontSize" }, { key = "+", mods = "Control", action = "IncreaseFontSize" }, { key = "-", mods = "Control", action = "DecreaseFontSize" }, { key = "0", mods = "Control", action = "ResetFontSize" }]
+"#;
+        assert_eq!(result.unwrap_or_default(), expected)
+    }
+
+    #[test]
+    fn test_alacritty_font_faces() {
+        let conf = config::Config {
+            backend: Some(config::Backend::Alacritty),
+            fonts: vec!["test_font".into()],
+            font: config::Font {
+                normal: Some(config::FontFace {
+                    family: None,
+                    style: Some("Medium".to_string()),
+                }),
+                italic: Some(config::FontFace {
+                    family: Some("italic_font".to_string()),
+                    style: None,
+                }),
+                bold_italic: Some(config::FontFace {
+                    family: None,
+                    style: Some("Heavy Italic".to_string()),
+                }),
+                ..Default::default()
+            },
+            ..Default::default()
+        };
+        let mut alacritty = Alacritty {
+            exe_path: PathBuf::new(),
+            cfg_file: None,
+        };
+        alacritty.create_conf_file(&mut DocumentMut::new(), &conf);
+        let tmp_conf = alacritty.cfg_file;
+        let result = fs::read_to_string(tmp_conf.as_ref().unwrap().path());
+        let expected = r#"[font]
+
+[font.normal]
+family = "test_font"
+style = "Medium"
+
+[font.italic]
+family = "italic_font"
+
+[font.bold_italic]
+style = "Heavy Italic"
+
+[keyboard]
+bindings = [{ key = "Z", mods = "Control", action = "None" }, { key = "=", mods = "Control", action = "IncreaseFontSize" }, { key = "+", mods = "Control", action = "IncreaseFontSize" }, { key = "-", mods = "Control", action = "DecreaseFontSize" }, { key = "0", mods = "Control", action = "ResetFontSize" }]
+"#;
+        assert_eq!(result.unwrap_or_default(), expected)
+    }
+
+    #[test]
+    fn test_alacritty_spacing() {
+        let conf = config::Config {
+            backend: Some(config::Backend::Alacritty),
+            line_height: Some(2),
+            letter_spacing: Some(-1),
+            ..Default::default()
+        };
+        let mut alacritty = Alacritty {
+            exe_path: PathBuf::new(),
+            cfg_file: None,
+        };
+        alacritty.create_conf_file(&mut DocumentMut::new(), &conf);
+        let tmp_conf = alacritty.cfg_file;
+        let result = fs::read_to_string(tmp_conf.as_ref().unwrap().path());
+        let expected = r#"[font]
+offset = { x = -1, y = 2 }
+
+[keyboard]
+bindings = [{ key = "Z", mods = "Control", action = "None" }, { key = "=", mods = "Control", action = "IncreaseFontSize" }, { key = "+", mods = "Control", action = "IncreaseFontSize" }, { key = "-", mods = "Control", action = "DecreaseFontSize" }, { key = "0", mods = "Control", action = "ResetFontSize" }]
+"#;
+        assert_eq!(result.unwrap_or_default(), expected)
+    }
+
+    #[test]
+    fn test_alacritty_passthrough_all_keys() {
+        let conf = config::Config {
+            backend: Some(config::Backend::Alacritty),
+            passthrough_all_keys: true,
+            keys: vec![config::KeyBinding {
+                key: "ctrl+shift+c".parse().unwrap(),
+                action: config::KeyAction::Copy,
+            }],
+            ..Default::default()
+        };
+        let mut alacritty = Alacritty {
+            exe_path: PathBuf::new(),
+            cfg_file: None,
+        };
+        alacritty.create_conf_file(&mut DocumentMut::new(), &conf);
+        let doc: DocumentMut = fs::read_to_string(alacritty.cfg_file.as_ref().unwrap().path())
+            .unwrap()
+            .parse()
+            .unwrap();
+        let bindings = doc["keyboard"]["bindings"].as_array().unwrap();
+        assert_eq!(bindings.len(), DEFAULT_SHORTCUTS.len() + 1);
+        let binding = |i: usize| bindings.get(i).unwrap().as_inline_table().unwrap();
+        assert_eq!(binding(1)["key"].as_str(), Some("C"));
+        assert_eq!(binding(1)["action"].as_str(), Some("Copy"));
+        assert_eq!(binding(2)["key"].as_str(), Some("V"));
+        assert_eq!(binding(2)["action"].as_str(), Some("ReceiveChar"));
+    }
+
+    #[test]
+    fn test_alacritty_cursor() {
+        let conf = config::Config {
+            backend: Some(config::Backend::Alacritty),
+            cursor: config::Cursor {
+                shape: Some(config::CursorShape::Beam),
+                blink: Some(true),
+                blink_interval: Some(500),
+                ..Default::default()
+            },
+            ..Default::default()
+        };
+        let mut alacritty = Alacritty {
+            exe_path: PathBuf::new(),
+            cfg_file: None,
+        };
+        alacritty.create_conf_file(&mut DocumentMut::new(), &conf);
+        let tmp_conf = alacritty.cfg_file;
+        let result = fs::read_to_string(tmp_conf.as_ref().unwrap().path());
+        let expected = r#"[cursor]
+blink_interval = 500
+
+[cursor.style]
+shape = "Beam"
+blinking = "On"
+
+[keyboard]
+bindings = [{ key = "Z", mods = "Control", action = "None" }, { key = "=", mods = "Control", action = "IncreaseFontSize" }, { key = "+", mods = "Control", action = "IncreaseFontSize" }, { key = "-", mods = "Control", action = "DecreaseFontSize" }, { key = "0", mods = "Control", action = "ResetFontSize" }]
 "#;
         assert_eq!(result.unwrap_or_default(), expected)
     }
@@ -328,8 +734,8 @@ size = 16
         let conf = config::Config {
             backend: Some(config::Backend::Alacritty),
             term_config_path: Some(term_conf_file.path().to_str().unwrap().to_string()),
-            font_size: 14,
-            fonts: vec!["test_font".to_string()],
+            font_size: 14.0,
+            fonts: vec!["test_font".into()],
             ..Default::default()
         };
         let mut alacritty = Alacritty {
@@ -346,13 +752,13 @@ size = 16
 TERM = "some"
 
 [font]
-size = 14
+size = 14.0
 
 [font.normal]
 family = "test_font"
 
 [keyboard]
-bindings = [{ key = "Z", mods = "Control", action = "None" }]
+bindings = [{ key = "Z", mods = "Control", action = "None" }, { key = "=", mods = "Control", action = "IncreaseFontSize" }, { key = "+", mods = "Control", action = "IncreaseFontSize" }, { key = "-", mods = "Control", action = "DecreaseFontSize" }, { key = "0", mods = "Control", action = "ResetFontSize" }]
 "#;
         assert_eq!(result.unwrap_or_default(), expected)
     }
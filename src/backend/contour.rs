@@ -0,0 +1,479 @@
+use super::Functions;
+use crate::config::{Config, CursorShape, KeyAction, KeyChord};
+use crate::error::GlrnvimError;
+use serde_yaml::{Mapping, Value};
+use std::fs;
+use std::path::PathBuf;
+use tempfile::NamedTempFile;
+extern crate log;
+
+pub const CONTOUR_NAME: &str = "contour";
+
+const DEFAULT_PROFILE: &str = "main";
+const COLOR_SCHEME: &str = "glrnvim";
+
+struct Contour {
+    exe_path: PathBuf,
+    cfg_file: Option<NamedTempFile>,
+}
+
+pub fn init(config: &Config) -> Result<Box<dyn Functions>, GlrnvimError> {
+    let exe_path = super::exe_path(&config.term_exe_path, CONTOUR_NAME)?;
+
+    Ok(Box::new(Contour {
+        exe_path,
+        cfg_file: None,
+    }))
+}
+
+// Get the child mapping with the given key. Create it if it doesn't exist.
+fn child_mapping<'a>(parent: &'a mut Mapping, key: &str) -> &'a mut Mapping {
+    if !parent.get(key).is_some_and(|v| v.is_mapping()) {
+        parent.insert(Value::from(key), Value::Mapping(Mapping::new()));
+    }
+    parent.get_mut(key).unwrap().as_mapping_mut().unwrap()
+}
+
+// The key and the modifiers in contour's format, e.g. ("C", ["Control", "Shift"]).
+fn contour_key(key: &KeyChord) -> (String, Vec<&'static str>) {
+    let name = match key.key.as_str() {
+        "tab" => "Tab".to_string(),
+        "enter" => "Enter".to_string(),
+        "escape" => "Escape".to_string(),
+        "space" => "Space".to_string(),
+        "backspace" => "Backspace".to_string(),
+        "delete" => "Delete".to_string(),
+        "insert" => "Insert".to_string(),
+        "home" => "Home".to_string(),
+        "end" => "End".to_string(),
+        "page_up" => "PageUp".to_string(),
+        "page_down" => "PageDown".to_string(),
+        "up" => "UpArrow".to_string(),
+        "down" => "DownArrow".to_string(),
+        "left" => "LeftArrow".to_string(),
+        "right" => "RightArrow".to_string(),
+        "plus" => "Plus".to_string(),
+        "minus" => "Minus".to_string(),
+        "equal" => "Equal".to_string(),
+        k => k.to_uppercase(),
+    };
+    let mods = super::key_mods(key, ["Control", "Shift", "Alt", "Super"]);
+    (name, mods)
+}
+
+// Check if the given input mapping is bound to the key.
+fn is_bound_to(input_mapping: &Value, key: &KeyChord) -> bool {
+    let (name, mods) = contour_key(key);
+    let same_key = input_mapping
+        .get("key")
+        .and_then(|k| k.as_str())
+        .is_some_and(|k| k.eq_ignore_ascii_case(&name));
+    let same_mods = input_mapping
+        .get("mods")
+        .and_then(|m| m.as_sequence())
+        .is_some_and(|m| {
+            m.len() == mods.len()
+                && m.iter()
+                    .all(|v| v.as_str().is_some_and(|v| mods.contains(&v)))
+        });
+    same_key && same_mods
+}
+
+impl Contour {
+    fn create_base_conf(&mut self, config: &Config) -> Mapping {
+        config.term_config_path.as_ref().map_or_else(
+            {
+                || match config.load_term_conf {
+                    true => Contour::load_contour_conf(None),
+                    _ => Mapping::new(),
+                }
+            },
+            |p| Contour::load_contour_conf(Some(p.to_string())),
+        )
+    }
+
+    fn create_conf_file(&mut self, base_mapping: &mut Mapping, config: &Config) {
+        // The settings are applied to the default profile.
+        let profile_name = match base_mapping.get("default_profile").and_then(|p| p.as_str()) {
+            Some(p) => p.to_string(),
+            None => {
+                base_mapping.insert(Value::from("default_profile"), Value::from(DEFAULT_PROFILE));
+                DEFAULT_PROFILE.to_string()
+            }
+        };
+
+        if !config.fonts.is_empty() || config.font_size > 0.0 {
+            let profiles = child_mapping(base_mapping, "profiles");
+            let profile = child_mapping(profiles, &profile_name);
+            let font = child_mapping(profile, "font");
+            if config.font_size > 0.0 {
+                font.insert(Value::from("size"), Value::from(config.font_size));
+            }
+            if let Some(family) = config.fonts.first() {
+                // Contour doesn't support fallback fonts. Just use the first chosen font.
+                let regular = child_mapping(font, "regular");
+                regular.insert(Value::from("family"), Value::from(family.to_string()));
+            }
+        }
+
+        let window = &config.window;
+        if window.size().is_some()
+            || window.maximized
+            || window.fullscreen
+            || window.decorations.is_some()
+            || window.padding.is_some()
+            || window.opacity.is_some()
+        {
+            let profiles = child_mapping(base_mapping, "profiles");
+            let profile = child_mapping(profiles, &profile_name);
+            if let Some((columns, lines)) = window.size() {
+                let terminal_size = child_mapping(profile, "terminal_size");
+                terminal_size.insert(Value::from("columns"), Value::from(columns));
+                terminal_size.insert(Value::from("lines"), Value::from(lines));
+            }
+            if window.fullscreen {
+                profile.insert(Value::from("fullscreen"), Value::from(true));
+            } else if window.maximized {
+                profile.insert(Value::from("maximized"), Value::from(true));
+            }
+            if let Some(decorations) = window.decorations {
+                profile.insert(Value::from("show_title_bar"), Value::from(decorations));
+            }
+            if let Some(padding) = window.padding {
+                let margins = child_mapping(profile, "margins");
+                margins.insert(Value::from("horizontal"), Value::from(padding.x));
+                margins.insert(Value::from("vertical"), Value::from(padding.y));
+            }
+            if let Some(opacity) = window.opacity {
+                let background = child_mapping(profile, "background");
+                background.insert(Value::from("opacity"), Value::from(opacity));
+            }
+        }
+
+        let cursor = &config.cursor;
+        if cursor.has_style() {
+            let profiles = child_mapping(base_mapping, "profiles");
+            let profile = child_mapping(profiles, &profile_name);
+            let cursor_mapping = child_mapping(profile, "cursor");
+            if let Some(shape) = cursor.shape {
+                let shape = match shape {
+                    CursorShape::Block => "block",
+                    CursorShape::Beam => "bar",
+                    CursorShape::Underline => "underscore",
+                };
+                cursor_mapping.insert(Value::from("shape"), Value::from(shape));
+            }
+            if let Some(blink) = cursor.blink {
+                cursor_mapping.insert(Value::from("blinking"), Value::from(blink));
+            }
+            if let Some(interval) = cursor.blink_interval {
+                cursor_mapping.insert(Value::from("blinking_interval"), Value::from(interval));
+            }
+        }
+
+        if !config.colors.is_empty() {
+            let scheme = Contour::color_scheme(config);
+            let schemes = child_mapping(base_mapping, "color_schemes");
+            schemes.insert(Value::from(COLOR_SCHEME), Value::Mapping(scheme));
+            let profiles = child_mapping(base_mapping, "profiles");
+            let profile = child_mapping(profiles, &profile_name);
+            profile.insert(Value::from("colors"), Value::from(COLOR_SCHEME));
+        }
+
+        // Disable some improper key bindings for nvim
+        // Contour cannot swallow a key, so the unbound keys are only removed from the mappings.
+        let bindings = config.key_bindings();
+        if config.passthrough_all_keys {
+            // An empty list replaces the default mappings of contour.
+            base_mapping.insert(Value::from("input_mapping"), Value::Sequence(Vec::new()));
+        }
+        if let Some(Value::Sequence(input_mappings)) = base_mapping.get_mut("input_mapping") {
+            input_mappings.retain(|m| !bindings.iter().any(|b| is_bound_to(m, &b.key)));
+        }
+        let mut new_mappings: Vec<Value> = Vec::new();
+        for binding in bindings {
+            let action = match binding.action {
+                KeyAction::Unbind | KeyAction::Passthrough => continue,
+                KeyAction::CsiU => "SendChars",
+                KeyAction::Copy => "CopySelection",
+                KeyAction::Paste => "PasteClipboard",
+                KeyAction::IncreaseFontSize => "IncreaseFontSize",
+                KeyAction::DecreaseFontSize => "DecreaseFontSize",
+                KeyAction::ResetFontSize => "ResetFontSize",
+                KeyAction::ToggleFullscreen => "ToggleFullscreen",
+            };
+            let (name, mods) = contour_key(&binding.key);
+            let mut mapping = Mapping::new();
+            mapping.insert(
+                Value::from("mods"),
+                Value::Sequence(mods.into_iter().map(Value::from).collect()),
+            );
+            mapping.insert(Value::from("key"), Value::from(name));
+            mapping.insert(Value::from("action"), Value::from(action));
+            if binding.action == KeyAction::CsiU {
+                let chars = format!("\x1b[{}", super::csi_u(&binding.key));
+                mapping.insert(Value::from("chars"), Value::from(chars));
+            }
+            new_mappings.push(Value::Mapping(mapping));
+        }
+        if !new_mappings.is_empty() {
+            if !base_mapping
+                .get("input_mapping")
+                .is_some_and(|m| m.is_sequence())
+            {
+                base_mapping.insert(Value::from("input_mapping"), Value::Sequence(Vec::new()));
+            }
+            if let Some(Value::Sequence(input_mappings)) = base_mapping.get_mut("input_mapping") {
+                input_mappings.extend(new_mappings);
+            }
+        }
+
+        let yaml_str = serde_yaml::to_string(base_mapping).unwrap();
+
+        // Write to a temp file to be loaded by contour
+        let file = tempfile::Builder::new().suffix(".yml").tempfile().unwrap();
+        fs::write(&file, yaml_str.as_bytes()).unwrap();
+
+        self.cfg_file = Some(file);
+    }
+
+    // Create the color scheme from the glrnvim config.
+    fn color_scheme(config: &Config) -> Mapping {
+        let colors = &config.colors;
+        let mut scheme = Mapping::new();
+        for (group, key, color) in [
+            ("default", "foreground", &colors.foreground),
+            ("default", "background", &colors.background),
+            ("cursor", "default", &colors.cursor),
+            ("selection", "foreground", &colors.selection_foreground),
+            ("selection", "background", &colors.selection_background),
+        ] {
+            if let Some(c) = color {
+                child_mapping(&mut scheme, group)
+                    .insert(Value::from(key), Value::from(c.to_string()));
+            }
+        }
+        for (i, c) in colors.palette.iter().enumerate() {
+            let group = if i < 8 { "normal" } else { "bright" };
+            child_mapping(&mut scheme, group).insert(
+                Value::from(super::ANSI_COLOR_NAMES[i % 8]),
+                Value::from(c.to_string()),
+            );
+        }
+        scheme
+    }
+
+    // Load the default contour config
+    fn load_contour_conf(path: Option<String>) -> Mapping {
+        let conf_path = path.or({
+            let base_confs: [String; 0] = [];
+            let pri_confs: [String; 2] = [
+                "$XDG_CONFIG_HOME/contour/contour.yml".to_string(),
+                "$HOME/.config/contour/contour.yml".to_string(),
+            ];
+            super::find_term_conf_files(&base_confs, &pri_confs)
+                .first()
+                .cloned()
+        });
+        match conf_path {
+            Some(p) => {
+                let content = std::fs::read_to_string(p.clone())
+                    .unwrap_or_else(|_| panic!("Cannot load term config file: '{}'", p));
+                match serde_yaml::from_str::<Mapping>(&content) {
+                    Ok(mapping) => mapping,
+                    Err(msg) => {
+                        log::warn!("Cannot parse term config file '{}'. {}", p, msg);
+                        Mapping::new()
+                    }
+                }
+            }
+            _ => Mapping::new(),
+        }
+    }
+}
+
+impl Functions for Contour {
+    fn create_command(&mut self, config: &Config) -> std::process::Command {
+        let mut base_mapping = self.create_base_conf(config);
+
+        self.create_conf_file(&mut base_mapping, config);
+        let mut command = std::process::Command::new(&self.exe_path);
+        command.arg("config");
+        command.arg(self.cfg_file.as_ref().unwrap().path());
+        command.arg("class");
+        command.arg("glrnvim");
+
+        if let Ok(current_dir) = std::env::current_dir() {
+            command.arg("working-directory");
+            command.arg(current_dir);
+        }
+
+        command.arg(&config.nvim_exe_path);
+        command.args(super::common_args(config));
+
+        command
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config;
+
+    #[test]
+    fn test_create_basic_contour_conf() {
+        let conf = config::Config {
+            backend: Some(config::Backend::Contour),
+            font_size: 14.0,
+            fonts: vec!["test_font".into()],
+            ..Default::default()
+        };
+        let mut contour = Contour {
+            exe_path: PathBuf::new(),
+            cfg_file: None,
+        };
+        contour.create_conf_file(&mut Mapping::new(), &conf);
+        let cfg_file = contour.cfg_file;
+        assert!(cfg_file.is_some());
+        let result = fs::read_to_string(cfg_file.as_ref().unwrap().path());
+        let expected = r#"default_profile: main
+profiles:
+  main:
+    font:
+      size: 14.0
+      regular:
+        family: test_font
+input_mapping:
+- mods:
+  - Control
+  key: Equal
+  action: IncreaseFontSize
+- mods:
+  - Control
+  key: Plus
+  action: IncreaseFontSize
+- mods:
+  - Control
+  key: Minus
+  action: DecreaseFontSize
+- mods:
+  - Control
+  key: '0'
+  action: ResetFontSize
+"#;
+        assert_eq!(result.unwrap_or_default(), expected)
+    }
+
+    #[test]
+    fn test_contour_colors() {
+        let conf = config::Config {
+            backend: Some(config::Backend::Contour),
+            colors: config::Colors {
+                foreground: Some("#c5c8c6".parse().unwrap()),
+                cursor: Some("#aabbcc".parse().unwrap()),
+                ..Default::default()
+            },
+            ..Default::default()
+        };
+        let mut contour = Contour {
+            exe_path: PathBuf::new(),
+            cfg_file: None,
+        };
+        contour.create_conf_file(&mut Mapping::new(), &conf);
+        let cfg_file = contour.cfg_file;
+        let result = fs::read_to_string(cfg_file.as_ref().unwrap().path());
+        let expected = r##"default_profile: main
+color_schemes:
+  glrnvim:
+    default:
+      foreground: '#c5c8c6'
+    cursor:
+      default: '#aabbcc'
+profiles:
+  main:
+    colors: glrnvim
+input_mapping:
+- mods:
+  - Control
+  key: Equal
+  action: IncreaseFontSize
+- mods:
+  - Control
+  key: Plus
+  action: IncreaseFontSize
+- mods:
+  - Control
+  key: Minus
+  action: DecreaseFontSize
+- mods:
+  - Control
+  key: '0'
+  action: ResetFontSize
+"##;
+        assert_eq!(result.unwrap_or_default(), expected)
+    }
+
+    #[test]
+    fn test_overwrite_contour_conf() {
+        let term_conf = r#"
+default_profile: work
+profiles:
+  work:
+    font:
+      size: 42
+      regular:
+        family: old_font
+        weight: bold
+input_mapping:
+  - { mods: [Control], key: 'Z', action: ScreenshotVT }
+  - { mods: [Control, Shift], key: 'C', action: CopySelection }
+"#;
+        let mut term_conf: Mapping = serde_yaml::from_str(term_conf).unwrap();
+        let conf = config::Config {
+            backend: Some(config::Backend::Contour),
+            font_size: 14.0,
+            fonts: vec!["test_font".into()],
+            ..Default::default()
+        };
+        let mut contour = Contour {
+            exe_path: PathBuf::new(),
+            cfg_file: None,
+        };
+        contour.create_conf_file(&mut term_conf, &conf);
+        let cfg_file = contour.cfg_file;
+        assert!(cfg_file.is_some());
+        let result = fs::read_to_string(cfg_file.as_ref().unwrap().path());
+        let expected = r#"default_profile: work
+profiles:
+  work:
+    font:
+      size: 14.0
+      regular:
+        family: test_font
+        weight: bold
+input_mapping:
+- mods:
+  - Control
+  - Shift
+  key: C
+  action: CopySelection
+- mods:
+  - Control
+  key: Equal
+  action: IncreaseFontSize
+- mods:
+  - Control
+  key: Plus
+  action: IncreaseFontSize
+- mods:
+  - Control
+  key: Minus
+  action: DecreaseFontSize
+- mods:
+  - Control
+  key: '0'
+  action: ResetFontSize
+"#;
+        assert_eq!(result.unwrap_or_default(), expected)
+    }
+}
@@ -0,0 +1,148 @@
+use super::Functions;
+use crate::config::Config;
+use crate::error::GlrnvimError;
+use std::path::PathBuf;
+
+const EXE_PLACEHOLDER: &str = "{exe}";
+const NVIM_ARGS_PLACEHOLDER: &str = "{nvim_args}";
+
+struct Custom {
+    exe_path: PathBuf,
+    // The argv template without the executable.
+    template: Vec<String>,
+}
+
+pub fn init(config: &Config) -> Result<Box<dyn Functions>, GlrnvimError> {
+    let template = match config.custom_backend.as_ref() {
+        Some(custom) if !custom.args.is_empty() => custom.args.clone(),
+        _ => {
+            return Err(GlrnvimError::new(
+                "The custom backend requires custom_backend.args in the config.",
+            ));
+        }
+    };
+
+    let exe_path = if template[0] == EXE_PLACEHOLDER {
+        match config.term_exe_path.as_ref() {
+            Some(p) => PathBuf::from(p),
+            None => {
+                return Err(GlrnvimError::new(
+                    "'{exe}' in custom_backend.args requires term_exe_path.",
+                ));
+            }
+        }
+    } else {
+        let exe_name = String::from(shellexpand::full(&template[0]).unwrap_or_default());
+        super::exe_path(&config.term_exe_path, &exe_name)?
+    };
+
+    Ok(Box::new(Custom {
+        exe_path,
+        template: template[1..].to_vec(),
+    }))
+}
+
+impl Custom {
+    fn expand_args(&self, config: &Config) -> Vec<String> {
+        let font = config
+            .fonts
+            .first()
+            .map(|f| f.family.clone())
+            .unwrap_or_default();
+        let fonts = config.font_families().join(",");
+        let size = if config.font_size > 0.0 {
+            config.font_size.to_string()
+        } else {
+            String::new()
+        };
+        let cwd = match std::env::current_dir() {
+            Ok(dir) => dir.display().to_string(),
+            Err(_) => String::new(),
+        };
+        let exe = self.exe_path.display().to_string();
+
+        let mut args: Vec<String> = Vec::new();
+        for arg in &self.template {
+            if arg == NVIM_ARGS_PLACEHOLDER {
+                args.extend(super::common_args(config));
+                continue;
+            }
+            args.push(
+                arg.replace(EXE_PLACEHOLDER, &exe)
+                    .replace("{nvim}", &config.nvim_exe_path)
+                    .replace("{fonts}", &fonts)
+                    .replace("{font}", &font)
+                    .replace("{size}", &size)
+                    .replace("{cwd}", &cwd),
+            );
+        }
+        args
+    }
+}
+
+impl Functions for Custom {
+    fn create_command(&mut self, config: &Config) -> std::process::Command {
+        let mut command = std::process::Command::new(&self.exe_path);
+        command.args(self.expand_args(config));
+        command
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config;
+
+    #[test]
+    fn test_expand_custom_args() {
+        let conf = config::Config {
+            backend: Some(config::Backend::Custom),
+            font_size: 14.0,
+            fonts: vec!["test_font".into(), "fallback_font".into()],
+            ..Default::default()
+        };
+        let custom = Custom {
+            exe_path: PathBuf::from("/path/to/term"),
+            template: vec![
+                "--font".to_string(),
+                "{font}:{size}".to_string(),
+                "--fonts={fonts}".to_string(),
+                "--class".to_string(),
+                "glrnvim".to_string(),
+                "-e".to_string(),
+                "{nvim}".to_string(),
+                "{nvim_args}".to_string(),
+            ],
+        };
+        let mut expected = vec![
+            "--font",
+            "test_font:14",
+            "--fonts=test_font,fallback_font",
+            "--class",
+            "glrnvim",
+            "-e",
+            "nvim",
+        ];
+        let common_args = super::super::common_args(&conf);
+        expected.extend(common_args.iter().map(|s| s.as_str()));
+        assert_eq!(custom.expand_args(&conf), expected);
+    }
+
+    #[test]
+    fn test_custom_exe_requires_term_exe_path() {
+        let conf = config::Config {
+            backend: Some(config::Backend::Custom),
+            custom_backend: Some(config::CustomBackend {
+                args: vec!["{exe}".to_string(), "-e".to_string()],
+            }),
+            ..Default::default()
+        };
+        assert!(init(&conf).is_err());
+
+        let conf = config::Config {
+            term_exe_path: Some("/path/to/term".to_string()),
+            ..conf
+        };
+        assert!(init(&conf).is_ok());
+    }
+}
@@ -1,13 +1,33 @@
 use super::Functions;
-use crate::config::Config;
+use crate::config::{Config, CursorShape, KeyAction, KeyChord};
 use crate::error::GlrnvimError;
-use ini::Ini;
+use ini::{EscapePolicy, Ini};
+use std::fs;
 use std::io::Write;
 use std::path::PathBuf;
 use tempfile::NamedTempFile;
 
 pub const FOOT_NAME: &str = "foot";
 
+// The default key bindings of foot which may conflict with nvim, and the actions of glrnvim.
+const DEFAULT_KEY_BINDINGS: &[(&str, &str)] = &[
+    ("clipboard-copy", "Control+Shift+c XF86Copy"),
+    ("clipboard-paste", "Control+Shift+v XF86Paste"),
+    ("primary-paste", "Shift+Insert"),
+    ("search-start", "Control+Shift+r"),
+    ("font-increase", "Control+plus Control+equal Control+KP_Add"),
+    ("font-decrease", "Control+minus Control+KP_Subtract"),
+    ("font-reset", "Control+0 Control+KP_0"),
+    ("spawn-terminal", "Control+Shift+n"),
+    ("show-urls-launch", "Control+Shift+o"),
+    ("unicode-input", "Control+Shift+u"),
+    ("prompt-prev", "Control+Shift+z"),
+    ("prompt-next", "Control+Shift+x"),
+    ("scrollback-up-page", "Shift+Prior"),
+    ("scrollback-down-page", "Shift+Next"),
+    ("fullscreen", "none"),
+];
+
 struct Foot {
     exe_path: PathBuf,
     temp_file: Option<NamedTempFile>,
@@ -24,45 +44,270 @@ pub fn init(config: &Config) -> Result<Box<dyn Functions>, GlrnvimError> {
 
 impl Foot {
     fn create_conf_file(&mut self, config: &Config) {
-        let mut foot_conf = if config.load_term_conf {
-            let conf_files = Foot::find_default_confs();
-            if conf_files.is_empty() {
-                Ini::new()
-            } else {
-                let path = conf_files.first().unwrap();
-                Ini::load_from_file(path).expect("Failed to load default config file")
-            }
-        } else {
-            Ini::new()
+        // The given config, or the default one, is copied as it is. The generated settings are
+        // appended to override it.
+        let base_path = match config.term_config_path.as_ref() {
+            Some(path) => Some(path.clone()),
+            None if config.load_term_conf => Foot::find_default_confs().first().cloned(),
+            None => None,
         };
+        let base = base_path.as_ref().map_or(String::new(), |path| {
+            fs::read_to_string(path).expect("Failed to load the config file")
+        });
+        // Only used to look up the key bindings of the base config.
+        let base_conf = Ini::load_from_str_noescape(&base).unwrap_or_else(|e| {
+            log::warn!("Failed to read the key bindings of the foot config: {}", e);
+            Ini::new()
+        });
+
+        let mut foot_conf = Ini::new();
 
-        let mut font_str = String::new();
-        for f in &config.fonts {
-            if !font_str.is_empty() {
-                font_str += ",";
+        if let Some(first) = config.fonts.first() {
+            let style = config.font.normal_style().map(|s| format!("style={}", s));
+            let font_str = Foot::font_str(&first.family, style.as_deref(), config);
+            foot_conf.with_section(Some("main")).set("font", font_str);
+
+            for (key, face) in config.font.faces() {
+                let Some(face) = face else { continue };
+                // Foot uses the family as it is when the face is set. Ask fontconfig for the
+                // bold or italic variants if no style is given.
+                let attrs = match (face.style.as_ref(), key) {
+                    (Some(style), _) => format!("style={}", style),
+                    (None, "bold") => "weight=bold".to_string(),
+                    (None, "italic") => "slant=italic".to_string(),
+                    (None, _) => "weight=bold:slant=italic".to_string(),
+                };
+                let family = face.family.as_ref().unwrap_or(&first.family);
+                let font_str = Foot::font_str(family, Some(&attrs), config);
+                foot_conf
+                    .with_section(Some("main"))
+                    .set(format!("font-{}", key.replace('_', "-")), font_str);
             }
-            font_str += f;
-            if config.font_size != 0 {
-                font_str += &format!(":size={}", config.font_size).to_string();
+        }
+
+        // Foot takes an absolute line height instead of the extra space, which depends on the font.
+        if config.line_height.is_some() {
+            log::warn!("line_height is not supported by foot.");
+        }
+        if let Some(letter_spacing) = config.letter_spacing {
+            foot_conf
+                .with_section(Some("main"))
+                .set("letter-spacing", format!("{}px", letter_spacing));
+        }
+
+        let colors = &config.colors;
+        for (key, color) in [
+            ("foreground", &colors.foreground),
+            ("background", &colors.background),
+            ("selection-foreground", &colors.selection_foreground),
+            ("selection-background", &colors.selection_background),
+        ] {
+            if let Some(c) = color {
+                foot_conf.with_section(Some("colors")).set(key, c.hex());
             }
         }
-        if !font_str.is_empty() {
-            foot_conf.with_section(Some("main")).set("font", font_str);
+        for (i, c) in colors.palette.iter().enumerate() {
+            let key = if i < 8 {
+                format!("regular{}", i)
+            } else {
+                format!("bright{}", i - 8)
+            };
+            foot_conf.with_section(Some("colors")).set(key, c.hex());
+        }
+        if let Some((columns, lines)) = config.window.size() {
+            foot_conf.with_section(Some("main")).set(
+                "initial-window-size-chars",
+                format!("{}x{}", columns, lines),
+            );
         }
-        // Note: The ctrl-z seems to be no-op so we don't have to disable it. Other default
-        // key bindings are quite harmless for now. If needed, just disable them in the config file
-        // here.
+
+        if let Some(padding) = config.window.padding {
+            foot_conf
+                .with_section(Some("main"))
+                .set("pad", format!("{}x{}", padding.x, padding.y));
+        }
+        if let Some(opacity) = config.window.opacity {
+            foot_conf
+                .with_section(Some("colors"))
+                .set("alpha", opacity.to_string());
+        }
+        if config.window.decorations == Some(false) {
+            foot_conf.with_section(Some("csd")).set("preferred", "none");
+        }
+
+        let cursor = &config.cursor;
+        if let Some(shape) = cursor.shape {
+            let shape = match shape {
+                CursorShape::Block => "block",
+                CursorShape::Beam => "beam",
+                CursorShape::Underline => "underline",
+            };
+            foot_conf.with_section(Some("cursor")).set("style", shape);
+        }
+        if let Some(blink) = cursor.blink {
+            foot_conf
+                .with_section(Some("cursor"))
+                .set("blink", if blink { "yes" } else { "no" });
+        }
+        if let Some(interval) = cursor.blink_interval {
+            foot_conf
+                .with_section(Some("cursor"))
+                .set("blink-rate", interval.to_string());
+        }
+
+        if let Some(cursor) = colors.cursor {
+            // The cursor color takes the text color under the cursor as well. Use the background
+            // color, or foot's default background color.
+            let text = colors.background.map_or("242424".to_string(), |c| c.hex());
+            foot_conf
+                .with_section(Some("cursor"))
+                .set("color", format!("{} {}", text, cursor.hex()));
+        }
+        foot_conf
+            .with_section(Some("main"))
+            .set("font-size-adjustment", super::FONT_SIZE_STEP.to_string());
+        Foot::set_key_bindings(&mut foot_conf, &base_conf, config);
 
         let mut file = tempfile::NamedTempFile::new().expect("Failed to create temporary file");
+        writeln!(file, "{}", base).expect("Failed to write to temporary file");
+        // Foot reads the escape sequences of the text bindings by itself.
         foot_conf
-            .write_to_file(&file)
+            .write_to_policy(&mut file, EscapePolicy::Nothing)
             .expect("Failed to write to temporary file");
         file.flush().unwrap();
 
-        file.path();
         self.temp_file = Some(file);
     }
 
+    // Foot binds keys to actions, instead of actions to keys. So the keys in the policy are removed
+    // from the actions they are bound to, then added to the glrnvim actions. Foot cannot swallow a
+    // key, so the unbound keys are only removed from the actions.
+    // Note: The ctrl-z seems to be no-op so we don't have to disable it.
+    fn set_key_bindings(foot_conf: &mut Ini, base_conf: &Ini, config: &Config) {
+        let bindings = config.key_bindings();
+        let keys: Vec<String> = bindings.iter().map(|b| Foot::key_name(&b.key)).collect();
+        for (action, default) in DEFAULT_KEY_BINDINGS {
+            let current = base_conf
+                .get_from(Some("key-bindings"), action)
+                .unwrap_or(default)
+                .to_string();
+            // All the default bindings are removed when passing through all keys.
+            let mut combos: Vec<String> = current
+                .split_whitespace()
+                .filter(|c| *c != "none" && !keys.iter().any(|k| k == c))
+                .filter(|_| !config.passthrough_all_keys)
+                .map(|c| c.to_string())
+                .collect();
+            for (binding, key) in bindings.iter().zip(&keys) {
+                let glrnvim_action = match binding.action {
+                    KeyAction::Copy => "clipboard-copy",
+                    KeyAction::Paste => "clipboard-paste",
+                    KeyAction::IncreaseFontSize => "font-increase",
+                    KeyAction::DecreaseFontSize => "font-decrease",
+                    KeyAction::ResetFontSize => "font-reset",
+                    KeyAction::ToggleFullscreen => "fullscreen",
+                    KeyAction::Unbind | KeyAction::Passthrough | KeyAction::CsiU => continue,
+                };
+                if glrnvim_action == *action {
+                    combos.push(key.to_string());
+                }
+            }
+
+            let combos = if combos.is_empty() {
+                "none".to_string()
+            } else {
+                combos.join(" ")
+            };
+            if combos != current {
+                foot_conf
+                    .with_section(Some("key-bindings"))
+                    .set(*action, combos);
+            }
+        }
+        // The keys of glrnvim are removed from the other bindings of the base config as well. The
+        // search and URL modes are left out, since they take all the keys anyway.
+        for section in ["key-bindings", "text-bindings"] {
+            let Some(props) = base_conf.section(Some(section)) else {
+                continue;
+            };
+            for (name, value) in props.iter() {
+                if section == "key-bindings" && DEFAULT_KEY_BINDINGS.iter().any(|(a, _)| *a == name)
+                {
+                    continue;
+                }
+                let (command, combos) = Foot::split_command(value);
+                let kept: Vec<&str> = combos
+                    .split_whitespace()
+                    .filter(|c| !keys.iter().any(|k| k == c))
+                    .collect();
+                if kept.len() == combos.split_whitespace().count() {
+                    continue;
+                }
+                let kept = if kept.is_empty() {
+                    "none".to_string()
+                } else {
+                    kept.join(" ")
+                };
+                foot_conf
+                    .with_section(Some(section))
+                    .set(name, format!("{}{}", command, kept));
+            }
+        }
+        // The keys sent in the CSI u encoding are text bindings.
+        for (binding, key) in bindings.iter().zip(&keys) {
+            if binding.action == KeyAction::CsiU {
+                foot_conf
+                    .with_section(Some("text-bindings"))
+                    .set(format!("\\x1b[{}", super::csi_u(&binding.key)), key);
+            }
+        }
+    }
+
+    // The key combination in foot's format, e.g. "Control+Shift+c".
+    // The command of a pipe binding with the following space, and the key combos, e.g.
+    // "[wl-copy] Control+y".
+    fn split_command(value: &str) -> (&str, &str) {
+        match value.find("] ") {
+            Some(end) if value.starts_with('[') => value.split_at(end + 2),
+            _ => ("", value),
+        }
+    }
+
+    fn key_name(key: &KeyChord) -> String {
+        let mut names = super::key_mods(key, ["Control", "Shift", "Mod1", "Mod4"]);
+        let keysym = super::x11_keysym(&key.key);
+        names.push(&keysym);
+        names.join("+")
+    }
+
+    // The font with the fallback fonts, e.g. "Family:style=Bold:size=12:fontfeatures=-liga,Fallback:size=10".
+    fn font_str(family: &str, attrs: Option<&str>, config: &Config) -> String {
+        let size = |size: f64| {
+            if size > 0.0 {
+                format!(":size={}", size)
+            } else {
+                String::new()
+            }
+        };
+        let mut font_str = family.to_string();
+        if let Some(attrs) = attrs {
+            font_str += &format!(":{}", attrs);
+        }
+        font_str += &size(config.font_size);
+        for feature in &config.font.features {
+            font_str += &format!(":fontfeatures={}", feature);
+        }
+        // The fallback fonts take their own sizes and styles.
+        for f in config.fonts.iter().skip(1) {
+            font_str += &format!(",{}", f.family);
+            if let Some(style) = f.style.as_ref() {
+                font_str += &format!(":style={}", style);
+            }
+            font_str += &size(f.size_or(config.font_size));
+        }
+        font_str
+    }
+
     fn find_default_confs() -> Vec<String> {
         let base_confs: [String; 0] = [];
         let pri_confs: [String; 3] = [
@@ -78,21 +323,262 @@ impl Functions for Foot {
     fn create_command(&mut self, config: &Config) -> std::process::Command {
         let mut command = std::process::Command::new(&self.exe_path);
 
+        // Overwrite the config with the generated settings from glrnvim.yml
+        self.create_conf_file(config);
         command.arg("--config");
-        if let Some(config_path) = config.term_config_path.as_ref() {
-            command.arg(config_path.as_str());
-        } else {
-            self.create_conf_file(config);
-            // Overwrite the config with the generated settings from glrnvim.yml
-            command.arg(self.temp_file.as_ref().unwrap().path());
-        }
+        command.arg(self.temp_file.as_ref().unwrap().path());
 
         command.arg("--app-id");
         command.arg("glrnvim");
 
+        // Wayland doesn't allow clients to set the window position.
+        if config.window.fullscreen {
+            command.arg("--fullscreen");
+        } else if config.window.maximized {
+            command.arg("--maximized");
+        }
+
         command.arg(&config.nvim_exe_path);
-        command.args(super::COMMON_ARGS);
+        command.args(super::common_args(config));
 
         command
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config;
+
+    #[test]
+    fn test_foot_colors() {
+        let conf = config::Config {
+            backend: Some(config::Backend::Foot),
+            colors: config::Colors {
+                background: Some("#1d1f21".parse().unwrap()),
+                cursor: Some("#c5c8c6".parse().unwrap()),
+                palette: (0..16)
+                    .map(|i| config::Color { r: i, g: i, b: i })
+                    .collect(),
+                ..Default::default()
+            },
+            ..Default::default()
+        };
+        let mut foot = Foot {
+            exe_path: PathBuf::new(),
+            temp_file: None,
+        };
+        foot.create_conf_file(&conf);
+        let foot_conf = Ini::load_from_file(foot.temp_file.as_ref().unwrap().path()).unwrap();
+        let colors = foot_conf.section(Some("colors")).unwrap();
+        assert_eq!(colors.get("background"), Some("1d1f21"));
+        assert_eq!(colors.get("regular0"), Some("000000"));
+        assert_eq!(colors.get("bright7"), Some("0f0f0f"));
+        assert_eq!(colors.len(), 17);
+        let cursor = foot_conf.section(Some("cursor")).unwrap();
+        assert_eq!(cursor.get("color"), Some("1d1f21 c5c8c6"));
+    }
+
+    #[test]
+    fn test_foot_window_geometry() {
+        let conf = config::Config {
+            backend: Some(config::Backend::Foot),
+            window: config::Window {
+                columns: Some(100),
+                lines: Some(40),
+                maximized: true,
+                fullscreen: true,
+                ..Default::default()
+            },
+            ..Default::default()
+        };
+        let mut foot = Foot {
+            exe_path: PathBuf::new(),
+            temp_file: None,
+        };
+        let command = foot.create_command(&conf);
+        // fullscreen wins over maximized.
+        assert!(command.get_args().any(|a| a == "--fullscreen"));
+        assert!(!command.get_args().any(|a| a == "--maximized"));
+        let foot_conf = Ini::load_from_file(foot.temp_file.as_ref().unwrap().path()).unwrap();
+        let main = foot_conf.section(Some("main")).unwrap();
+        assert_eq!(main.get("initial-window-size-chars"), Some("100x40"));
+    }
+
+    #[test]
+    fn test_foot_window_style() {
+        let conf = config::Config {
+            backend: Some(config::Backend::Foot),
+            window: config::Window {
+                decorations: Some(false),
+                padding: Some(config::Padding { x: 5, y: 2 }),
+                opacity: Some(0.9),
+                ..Default::default()
+            },
+            ..Default::default()
+        };
+        let mut foot = Foot {
+            exe_path: PathBuf::new(),
+            temp_file: None,
+        };
+        foot.create_conf_file(&conf);
+        let foot_conf = Ini::load_from_file(foot.temp_file.as_ref().unwrap().path()).unwrap();
+        assert_eq!(foot_conf.get_from(Some("main"), "pad"), Some("5x2"));
+        assert_eq!(foot_conf.get_from(Some("colors"), "alpha"), Some("0.9"));
+        assert_eq!(foot_conf.get_from(Some("csd"), "preferred"), Some("none"));
+    }
+
+    #[test]
+    fn test_foot_fractional_font_size() {
+        let conf = config::Config {
+            backend: Some(config::Backend::Foot),
+            font_size: 11.5,
+            fonts: vec!["test_font".into(), "fallback_font".into()],
+            ..Default::default()
+        };
+        let mut foot = Foot {
+            exe_path: PathBuf::new(),
+            temp_file: None,
+        };
+        foot.create_conf_file(&conf);
+        let foot_conf = Ini::load_from_file(foot.temp_file.as_ref().unwrap().path()).unwrap();
+        assert_eq!(
+            foot_conf.get_from(Some("main"), "font"),
+            Some("test_font:size=11.5,fallback_font:size=11.5")
+        );
+    }
+
+    #[test]
+    fn test_foot_key_bindings() {
+        let conf = config::Config {
+            backend: Some(config::Backend::Foot),
+            keys: vec![
+                config::KeyBinding {
+                    key: "ctrl+shift+c".parse().unwrap(),
+                    action: config::KeyAction::Passthrough,
+                },
+                config::KeyBinding {
+                    key: "ctrl+equal".parse().unwrap(),
+                    action: config::KeyAction::Passthrough,
+                },
+                config::KeyBinding {
+                    key: "alt+c".parse().unwrap(),
+                    action: config::KeyAction::Copy,
+                },
+            ],
+            ..Default::default()
+        };
+        let mut foot_conf = Ini::new();
+        Foot::set_key_bindings(&mut foot_conf, &Ini::new(), &conf);
+        let section = foot_conf.section(Some("key-bindings")).unwrap();
+        assert_eq!(section.get("clipboard-copy"), Some("XF86Copy Mod1+c"));
+        assert_eq!(
+            section.get("font-increase"),
+            Some("Control+KP_Add Control+plus")
+        );
+        assert_eq!(section.len(), 4);
+    }
+
+    #[test]
+    fn test_foot_clipboard() {
+        let conf = config::Config {
+            backend: Some(config::Backend::Foot),
+            clipboard: config::Clipboard {
+                copy: Some("ctrl+shift+c".parse().unwrap()),
+                paste: None,
+            },
+            ..Default::default()
+        };
+        let mut foot_conf = Ini::new();
+        Foot::set_key_bindings(&mut foot_conf, &Ini::new(), &conf);
+        let section = foot_conf.section(Some("key-bindings")).unwrap();
+        assert_eq!(section.get("clipboard-copy"), Some("XF86Copy"));
+        let section = foot_conf.section(Some("text-bindings")).unwrap();
+        assert_eq!(section.get("\\x1b[99;6u"), Some("Control+Shift+c"));
+    }
+
+    #[test]
+    fn test_foot_key_bindings_of_base_config() {
+        let base_conf = Ini::load_from_str_noescape(
+            "[key-bindings]\n\
+             pipe-visible=[sh -c \"xurls | fuzzel\"] Control+Shift+y\n\
+             scrollback-up-line=Control+Shift+k Mod1+k\n\
+             [text-bindings]\n\
+             \\x1b[1;5P=Control+Shift+k\n",
+        )
+        .unwrap();
+        let conf = config::Config {
+            backend: Some(config::Backend::Foot),
+            keys: ["ctrl+shift+y", "ctrl+shift+k"]
+                .map(|key| config::KeyBinding {
+                    key: key.parse().unwrap(),
+                    action: config::KeyAction::Passthrough,
+                })
+                .to_vec(),
+            ..Default::default()
+        };
+        let mut foot_conf = Ini::new();
+        Foot::set_key_bindings(&mut foot_conf, &base_conf, &conf);
+        let section = foot_conf.section(Some("key-bindings")).unwrap();
+        assert_eq!(
+            section.get("pipe-visible"),
+            Some("[sh -c \"xurls | fuzzel\"] none")
+        );
+        assert_eq!(section.get("scrollback-up-line"), Some("Mod1+k"));
+        assert_eq!(
+            foot_conf.get_from(Some("text-bindings"), "\\x1b[1;5P"),
+            Some("none")
+        );
+    }
+
+    #[test]
+    fn test_foot_passthrough_all_keys() {
+        let conf = config::Config {
+            backend: Some(config::Backend::Foot),
+            passthrough_all_keys: true,
+            keys: vec![config::KeyBinding {
+                key: "alt+v".parse().unwrap(),
+                action: config::KeyAction::Paste,
+            }],
+            ..Default::default()
+        };
+        let mut foot_conf = Ini::new();
+        Foot::set_key_bindings(&mut foot_conf, &Ini::new(), &conf);
+        let section = foot_conf.section(Some("key-bindings")).unwrap();
+        assert_eq!(section.get("clipboard-paste"), Some("Mod1+v"));
+        assert_eq!(section.get("clipboard-copy"), Some("none"));
+        assert_eq!(section.get("font-increase"), Some("none"));
+        assert!(section.get("fullscreen").is_none());
+    }
+
+    #[test]
+    fn test_foot_term_config_path() {
+        let base = "[main]\ninclude=~/.config/foot/theme.ini\n\n\
+                    [key-bindings]\nclipboard-copy=Control+Shift+c Mod1+w\n\n\
+                    [text-bindings]\n\\x1b[1;5P=Control+F1\n";
+        let mut term_conf = NamedTempFile::new().unwrap();
+        write!(term_conf, "{}", base).unwrap();
+        let conf = config::Config {
+            backend: Some(config::Backend::Foot),
+            term_config_path: Some(term_conf.path().display().to_string()),
+            fonts: vec!["test_font".into()],
+            keys: vec![config::KeyBinding {
+                key: "ctrl+shift+c".parse().unwrap(),
+                action: config::KeyAction::Passthrough,
+            }],
+            ..Default::default()
+        };
+        let mut foot = Foot {
+            exe_path: PathBuf::new(),
+            temp_file: None,
+        };
+        let command = foot.create_command(&conf);
+        let config_path = foot.temp_file.as_ref().unwrap().path();
+        assert!(command.get_args().any(|a| a == config_path));
+        // The given config is kept as it is, and the generated settings are appended.
+        let result = fs::read_to_string(config_path).unwrap();
+        let generated = result.strip_prefix(base).unwrap();
+        assert!(generated.contains("[main]\nfont=test_font\n"));
+        assert!(generated.contains("clipboard-copy=Mod1+w\n"));
+    }
+}
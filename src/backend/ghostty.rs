@@ -0,0 +1,304 @@
+use super::Functions;
+use crate::config::{Config, CursorShape, KeyAction};
+use crate::error::GlrnvimError;
+use std::io::Write;
+use std::path::PathBuf;
+use tempfile::NamedTempFile;
+
+pub const GHOSTTY_NAME: &str = "ghostty";
+
+struct Ghostty {
+    exe_path: PathBuf,
+    temp_file: Option<NamedTempFile>,
+}
+
+pub fn init(config: &Config) -> Result<Box<dyn Functions>, GlrnvimError> {
+    let exe_path = super::exe_path(&config.term_exe_path, GHOSTTY_NAME)?;
+
+    Ok(Box::new(Ghostty {
+        exe_path,
+        temp_file: None,
+    }))
+}
+
+impl Ghostty {
+    fn create_conf_file(&mut self, config: &Config) {
+        let mut file = tempfile::NamedTempFile::new().unwrap();
+
+        if !config.fonts.is_empty() {
+            // font-family is a repeatable key. An empty value resets the list which may have been
+            // set by the terminal's config, then the rest are used as fallback fonts by orders.
+            writeln!(file, "font-family = \"\"").unwrap();
+            for font in &config.fonts {
+                writeln!(file, "font-family = \"{}\"", font).unwrap();
+            }
+        }
+        let font = &config.font;
+        if let Some(style) = font.normal_style() {
+            writeln!(file, "font-style = \"{}\"", style).unwrap();
+        }
+        for (key, face) in font.faces() {
+            let Some(face) = face else { continue };
+            let key = key.replace('_', "-");
+            if let Some(family) = face.family.as_ref() {
+                writeln!(file, "font-family-{} = \"\"", key).unwrap();
+                writeln!(file, "font-family-{} = \"{}\"", key, family).unwrap();
+            }
+            if let Some(style) = face.style.as_ref() {
+                writeln!(file, "font-style-{} = \"{}\"", key, style).unwrap();
+            }
+        }
+        for feature in &font.features {
+            writeln!(file, "font-feature = {}", feature).unwrap();
+        }
+        if config.font_size > 0.0 {
+            writeln!(file, "font-size = {}", config.font_size).unwrap();
+        }
+
+        if let Some(line_height) = config.line_height {
+            writeln!(file, "adjust-cell-height = {}", line_height).unwrap();
+        }
+        if let Some(letter_spacing) = config.letter_spacing {
+            writeln!(file, "adjust-cell-width = {}", letter_spacing).unwrap();
+        }
+
+        let colors = &config.colors;
+        for (key, color) in [
+            ("foreground", &colors.foreground),
+            ("background", &colors.background),
+            ("cursor-color", &colors.cursor),
+            ("selection-foreground", &colors.selection_foreground),
+            ("selection-background", &colors.selection_background),
+        ] {
+            if let Some(c) = color {
+                writeln!(file, "{} = {}", key, c).unwrap();
+            }
+        }
+        for (i, c) in colors.palette.iter().enumerate() {
+            writeln!(file, "palette = {}={}", i, c).unwrap();
+        }
+
+        let window = &config.window;
+        if let Some((columns, lines)) = window.size() {
+            writeln!(file, "window-width = {}", columns).unwrap();
+            writeln!(file, "window-height = {}", lines).unwrap();
+        }
+        if let Some(position) = window.position {
+            writeln!(file, "window-position-x = {}", position.x).unwrap();
+            writeln!(file, "window-position-y = {}", position.y).unwrap();
+        }
+        if window.fullscreen {
+            writeln!(file, "fullscreen = true").unwrap();
+        } else if window.maximized {
+            writeln!(file, "maximize = true").unwrap();
+        }
+        if let Some(decorations) = window.decorations {
+            writeln!(file, "window-decoration = {}", decorations).unwrap();
+        }
+        if let Some(padding) = window.padding {
+            writeln!(file, "window-padding-x = {}", padding.x).unwrap();
+            writeln!(file, "window-padding-y = {}", padding.y).unwrap();
+        }
+        if let Some(opacity) = window.opacity {
+            writeln!(file, "background-opacity = {}", opacity).unwrap();
+        }
+
+        // Ghostty doesn't support the blink interval.
+        if let Some(shape) = config.cursor.shape {
+            let shape = match shape {
+                CursorShape::Block => "block",
+                CursorShape::Beam => "bar",
+                CursorShape::Underline => "underline",
+            };
+            writeln!(file, "cursor-style = {}", shape).unwrap();
+        }
+        if let Some(blink) = config.cursor.blink {
+            writeln!(file, "cursor-style-blink = {}", blink).unwrap();
+        }
+
+        writeln!(file, "class = glrnvim").unwrap();
+        if config.passthrough_all_keys {
+            writeln!(file, "keybind = clear").unwrap();
+        }
+        for binding in config.key_bindings() {
+            let action = match binding.action {
+                // unbind removes the binding and passes the key to the program, ignore swallows it.
+                KeyAction::Unbind => "ignore",
+                KeyAction::Passthrough => "unbind",
+                KeyAction::CsiU => &format!("csi:{}", super::csi_u(&binding.key)),
+                KeyAction::Copy => "copy_to_clipboard",
+                KeyAction::Paste => "paste_from_clipboard",
+                KeyAction::IncreaseFontSize => {
+                    &format!("increase_font_size:{}", super::FONT_SIZE_STEP)
+                }
+                KeyAction::DecreaseFontSize => {
+                    &format!("decrease_font_size:{}", super::FONT_SIZE_STEP)
+                }
+                KeyAction::ResetFontSize => "reset_font_size",
+                KeyAction::ToggleFullscreen => "toggle_fullscreen",
+            };
+            let mut key = super::key_mods(&binding.key, ["ctrl", "shift", "alt", "super"]);
+            let name = match binding.key.key.as_str() {
+                "up" => "arrow_up",
+                "down" => "arrow_down",
+                "left" => "arrow_left",
+                "right" => "arrow_right",
+                name => name,
+            };
+            key.push(name);
+            writeln!(file, "keybind = {}={}", key.join("+"), action).unwrap();
+        }
+        file.flush().unwrap();
+
+        self.temp_file = Some(file);
+    }
+}
+
+impl Functions for Ghostty {
+    fn create_command(&mut self, config: &Config) -> std::process::Command {
+        self.create_conf_file(config);
+
+        let mut command = std::process::Command::new(&self.exe_path);
+
+        // The default config files are loaded before the command line ones. Skip them if the user
+        // doesn't want them, or a specific config file has been given.
+        if config.term_config_path.is_some() || !config.load_term_conf {
+            command.arg("--config-default-files=false");
+        }
+        if let Some(config_path) = config.term_config_path.as_ref() {
+            command.arg(format!("--config-file={}", config_path));
+        }
+        // Overwrite the config with the generated settings from glrnvim.yml
+        command.arg(format!(
+            "--config-file={}",
+            self.temp_file.as_ref().unwrap().path().display()
+        ));
+
+        if let Ok(current_dir) = std::env::current_dir() {
+            command.arg(format!("--working-directory={}", current_dir.display()));
+        }
+
+        command.arg("-e");
+        command.arg(&config.nvim_exe_path);
+        command.args(super::common_args(config));
+
+        command
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config;
+    use std::fs;
+
+    #[test]
+    fn test_create_ghostty_conf() {
+        let conf = config::Config {
+            backend: Some(config::Backend::Ghostty),
+            font_size: 14.0,
+            fonts: vec!["test_font".into(), "fallback_font".into()],
+            ..Default::default()
+        };
+        let mut ghostty = Ghostty {
+            exe_path: PathBuf::new(),
+            temp_file: None,
+        };
+        ghostty.create_conf_file(&conf);
+        let result = fs::read_to_string(ghostty.temp_file.as_ref().unwrap().path());
+        let expected = r#"font-family = ""
+font-family = "test_font"
+font-family = "fallback_font"
+font-size = 14
+class = glrnvim
+keybind = ctrl+z=ignore
+keybind = ctrl+equal=increase_font_size:1
+keybind = ctrl+plus=increase_font_size:1
+keybind = ctrl+minus=decrease_font_size:1
+keybind = ctrl+0=reset_font_size
+"#;
+        assert_eq!(result.unwrap_or_default(), expected)
+    }
+
+    #[test]
+    fn test_ghostty_font_faces() {
+        let conf = config::Config {
+            backend: Some(config::Backend::Ghostty),
+            fonts: vec!["test_font".into()],
+            font: config::Font {
+                normal: Some(config::FontFace {
+                    family: None,
+                    style: Some("Medium".to_string()),
+                }),
+                bold_italic: Some(config::FontFace {
+                    family: Some("bold_italic_font".to_string()),
+                    style: Some("Heavy Italic".to_string()),
+                }),
+                features: vec!["-liga".to_string(), "+zero".to_string()],
+                ..Default::default()
+            },
+            ..Default::default()
+        };
+        let mut ghostty = Ghostty {
+            exe_path: PathBuf::new(),
+            temp_file: None,
+        };
+        ghostty.create_conf_file(&conf);
+        let result = fs::read_to_string(ghostty.temp_file.as_ref().unwrap().path());
+        let expected = r#"font-family = ""
+font-family = "test_font"
+font-style = "Medium"
+font-family-bold-italic = ""
+font-family-bold-italic = "bold_italic_font"
+font-style-bold-italic = "Heavy Italic"
+font-feature = -liga
+font-feature = +zero
+class = glrnvim
+keybind = ctrl+z=ignore
+keybind = ctrl+equal=increase_font_size:1
+keybind = ctrl+plus=increase_font_size:1
+keybind = ctrl+minus=decrease_font_size:1
+keybind = ctrl+0=reset_font_size
+"#;
+        assert_eq!(result.unwrap_or_default(), expected)
+    }
+
+    #[test]
+    fn test_ghostty_passthrough_all_keys() {
+        let conf = config::Config {
+            backend: Some(config::Backend::Ghostty),
+            passthrough_all_keys: true,
+            ..Default::default()
+        };
+        let mut ghostty = Ghostty {
+            exe_path: PathBuf::new(),
+            temp_file: None,
+        };
+        ghostty.create_conf_file(&conf);
+        let result = fs::read_to_string(ghostty.temp_file.as_ref().unwrap().path());
+        let expected = r#"class = glrnvim
+keybind = clear
+keybind = ctrl+z=ignore
+"#;
+        assert_eq!(result.unwrap_or_default(), expected)
+    }
+
+    #[test]
+    fn test_ghostty_term_config_path() {
+        let conf = config::Config {
+            backend: Some(config::Backend::Ghostty),
+            term_config_path: Some("/path/to/config".to_string()),
+            load_term_conf: true,
+            ..Default::default()
+        };
+        let mut ghostty = Ghostty {
+            exe_path: PathBuf::new(),
+            temp_file: None,
+        };
+        let command = ghostty.create_command(&conf);
+        let args: Vec<_> = command.get_args().collect();
+        assert_eq!(args[0], "--config-default-files=false");
+        assert_eq!(args[1], "--config-file=/path/to/config");
+        assert!(args[2].to_str().unwrap().starts_with("--config-file="));
+    }
+}
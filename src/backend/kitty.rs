@@ -1,5 +1,5 @@
 use super::Functions;
-use crate::config::Config;
+use crate::config::{Config, CursorShape, FontSpec, KeyAction};
 use crate::error::GlrnvimError;
 use std::io::Write;
 use std::path::PathBuf;
@@ -7,6 +7,27 @@ use tempfile::NamedTempFile;
 
 pub const KITTY_NAME: &str = "kitty";
 
+// The default unicode ranges of the fallback fonts, for the symbol fonts like Nerd Fonts.
+// The private use areas, and a few symbols patched by Nerd Fonts outside of them.
+const SYMBOL_RANGES: &[&str] = &[
+    "U+23FB-U+23FE",
+    "U+2665",
+    "U+26A1",
+    "U+2B58",
+    "U+E000-U+F8FF",
+    "U+F0000-U+FFFFD",
+];
+
+// For all other fallback fonts. The CJK characters, kana, hangul and the full width forms.
+const CJK_RANGES: &[&str] = &[
+    "U+2E80-U+9FFF",
+    "U+AC00-U+D7AF",
+    "U+F900-U+FAFF",
+    "U+FE30-U+FE4F",
+    "U+FF00-U+FFEF",
+    "U+20000-U+3134F",
+];
+
 struct Kitty {
     exe_path: PathBuf,
     temp_file: Option<NamedTempFile>,
@@ -25,27 +46,158 @@ impl Kitty {
     fn create_conf_file(&mut self, config: &Config) {
         let mut file = tempfile::NamedTempFile::new().unwrap();
 
-        if !config.fonts.is_empty() {
-            // Kitty's font fallback system is based on unicode range which is too
-            // difficult to support. Just use the first chosen font.
-            writeln!(file, "font_family {}", config.fonts.first().unwrap()).unwrap();
+        let font = &config.font;
+        if let Some(family) = config.fonts.first() {
+            if font.normal_style().is_none() && font.features.is_empty() {
+                writeln!(file, "font_family {}", family).unwrap();
+            } else {
+                let spec = Kitty::font_spec(&family.family, font.normal_style(), &font.features);
+                writeln!(file, "font_family {}", spec).unwrap();
+            }
         }
-        if config.font_size != 0 {
+        // Kitty picks the fallback fonts by itself. The chosen ones are mapped to unicode ranges.
+        for symbol_map in Kitty::symbol_maps(&config.fonts) {
+            writeln!(file, "{}", symbol_map).unwrap();
+        }
+        for (key, face) in font.faces() {
+            // The faces without a family use the same family as the normal one.
+            let Some(face) = face else { continue };
+            let primary = config.fonts.first().map(|f| &f.family);
+            let Some(family) = face.family.as_ref().or(primary) else {
+                continue;
+            };
+            let spec = Kitty::font_spec(family, face.style.as_deref(), &font.features);
+            writeln!(file, "{}_font {}", key, spec).unwrap();
+        }
+        if config.font_size > 0.0 {
             writeln!(file, "font_size {}", config.font_size).unwrap();
         }
 
-        if !config.load_term_conf {
+        if let Some(line_height) = config.line_height {
+            writeln!(file, "modify_font cell_height {:+}px", line_height).unwrap();
+        }
+        if let Some(letter_spacing) = config.letter_spacing {
+            writeln!(file, "modify_font cell_width {:+}px", letter_spacing).unwrap();
+        }
+
+        let colors = &config.colors;
+        for (key, color) in [
+            ("foreground", &colors.foreground),
+            ("background", &colors.background),
+            ("cursor", &colors.cursor),
+            ("selection_foreground", &colors.selection_foreground),
+            ("selection_background", &colors.selection_background),
+        ] {
+            if let Some(c) = color {
+                writeln!(file, "{} {}", key, c).unwrap();
+            }
+        }
+        for (i, c) in colors.palette.iter().enumerate() {
+            writeln!(file, "color{} {}", i, c).unwrap();
+        }
+
+        if let Some((columns, lines)) = config.window.size() {
+            writeln!(file, "remember_window_size no").unwrap();
+            writeln!(file, "initial_window_width {}c", columns).unwrap();
+            writeln!(file, "initial_window_height {}c", lines).unwrap();
+        }
+        if let Some(decorations) = config.window.decorations {
+            let hide = if decorations { "no" } else { "yes" };
+            writeln!(file, "hide_window_decorations {}", hide).unwrap();
+        }
+        if let Some(padding) = config.window.padding {
+            // The vertical padding comes first.
+            writeln!(file, "window_padding_width {} {}", padding.y, padding.x).unwrap();
+        }
+        if let Some(opacity) = config.window.opacity {
+            writeln!(file, "background_opacity {}", opacity).unwrap();
+        }
+
+        let cursor = &config.cursor;
+        if let Some(shape) = cursor.shape {
+            let shape = match shape {
+                CursorShape::Block => "block",
+                CursorShape::Beam => "beam",
+                CursorShape::Underline => "underline",
+            };
+            writeln!(file, "cursor_shape {}", shape).unwrap();
+        }
+        // The interval is in seconds. 0 disables blinking, and -1 uses the system default.
+        match (cursor.blink, cursor.blink_interval) {
+            (Some(false), _) => writeln!(file, "cursor_blink_interval 0").unwrap(),
+            (_, Some(interval)) => {
+                writeln!(file, "cursor_blink_interval {}", interval as f32 / 1000.0).unwrap()
+            }
+            (Some(true), None) => writeln!(file, "cursor_blink_interval -1").unwrap(),
+            (None, None) => {}
+        }
+
+        if !config.load_term_conf || config.passthrough_all_keys {
             writeln!(file, "clear_all_shortcuts yes").unwrap();
         }
-        // Using no_op to bypass ctrl-z seems not working.
-        // So choose a harmless action to bypass ctrl-z.
-        writeln!(file, "map ctrl+z change_font_size all 0").unwrap();
+        // The key names of kitty are the same as glrnvim's, e.g. "ctrl+shift+page_up".
+        for binding in config.key_bindings() {
+            let action = match binding.action {
+                // no_op passes the key to the program, discard_event swallows it.
+                KeyAction::Unbind => "discard_event",
+                KeyAction::Passthrough => "no_op",
+                KeyAction::CsiU => &format!("send_text all \\x1b[{}", super::csi_u(&binding.key)),
+                KeyAction::Copy => "copy_to_clipboard",
+                KeyAction::Paste => "paste_from_clipboard",
+                KeyAction::IncreaseFontSize => {
+                    &format!("change_font_size all +{:.1}", super::FONT_SIZE_STEP)
+                }
+                KeyAction::DecreaseFontSize => {
+                    &format!("change_font_size all -{:.1}", super::FONT_SIZE_STEP)
+                }
+                KeyAction::ResetFontSize => "change_font_size all 0",
+                KeyAction::ToggleFullscreen => "toggle_fullscreen",
+            };
+            writeln!(file, "map {} {}", binding.key, action).unwrap();
+        }
         file.flush().unwrap();
 
-        file.path();
         self.temp_file = Some(file);
     }
 
+    fn symbol_maps(fonts: &[FontSpec]) -> Vec<String> {
+        fonts
+            .iter()
+            .skip(1)
+            .map(|font| {
+                let ranges = if !font.unicode_ranges.is_empty() {
+                    font.unicode_ranges.join(",")
+                } else if Kitty::is_symbol_font(&font.family) {
+                    SYMBOL_RANGES.join(",")
+                } else {
+                    CJK_RANGES.join(",")
+                };
+                format!("symbol_map {} {}", ranges, font.family)
+            })
+            .collect()
+    }
+
+    fn is_symbol_font(family: &str) -> bool {
+        let family = family.to_lowercase();
+        ["nerd", "symbol", "powerline", "icon"]
+            .iter()
+            .any(|s| family.contains(s))
+    }
+
+    // font_features requires the PostScript names of the fonts, which are unknown here.
+    // Set the features in the font specifications instead, e.g.
+    // family="Fira Code" style="Retina" features="-liga +zero".
+    fn font_spec(family: &str, style: Option<&str>, features: &[String]) -> String {
+        let mut spec = format!("family=\"{}\"", family);
+        if let Some(style) = style {
+            spec.push_str(&format!(" style=\"{}\"", style));
+        }
+        if !features.is_empty() {
+            spec.push_str(&format!(" features=\"{}\"", features.join(" ")));
+        }
+        spec
+    }
+
     // See the config merge order in the kitty's man page.
     // /etc/xdg/kitty/kitty.conf is always used with the lowest priority.
     // By the listed orders, one of the following four will be picked if it exists.
@@ -91,9 +243,174 @@ impl Functions for Kitty {
             command.arg("glrnvim");
         }
 
+        if config.window.fullscreen {
+            command.arg("--start-as=fullscreen");
+        } else if config.window.maximized {
+            command.arg("--start-as=maximized");
+        }
+
         command.arg(&config.nvim_exe_path);
-        command.args(super::COMMON_ARGS);
+        command.args(super::common_args(config));
 
         command
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config;
+    use std::fs;
+
+    #[test]
+    fn test_kitty_colors() {
+        let conf = config::Config {
+            backend: Some(config::Backend::Kitty),
+            colors: config::Colors {
+                foreground: Some("#c5c8c6".parse().unwrap()),
+                palette: (0..16)
+                    .map(|i| config::Color { r: i, g: i, b: i })
+                    .collect(),
+                ..Default::default()
+            },
+            ..Default::default()
+        };
+        let mut kitty = Kitty {
+            exe_path: PathBuf::new(),
+            temp_file: None,
+        };
+        kitty.create_conf_file(&conf);
+        let result = fs::read_to_string(kitty.temp_file.as_ref().unwrap().path()).unwrap();
+        assert!(result.contains("foreground #c5c8c6\n"));
+        assert!(result.contains("color0 #000000\n"));
+        assert!(result.contains("color15 #0f0f0f\n"));
+        assert!(!result.contains("background"));
+    }
+
+    #[test]
+    fn test_kitty_window_geometry() {
+        let conf = config::Config {
+            backend: Some(config::Backend::Kitty),
+            window: config::Window {
+                columns: Some(100),
+                lines: Some(40),
+                maximized: true,
+                ..Default::default()
+            },
+            ..Default::default()
+        };
+        let mut kitty = Kitty {
+            exe_path: PathBuf::new(),
+            temp_file: None,
+        };
+        let command = kitty.create_command(&conf);
+        assert!(command.get_args().any(|a| a == "--start-as=maximized"));
+        let result = fs::read_to_string(kitty.temp_file.as_ref().unwrap().path()).unwrap();
+        assert!(result.contains(
+            "remember_window_size no\ninitial_window_width 100c\ninitial_window_height 40c\n"
+        ));
+    }
+
+    #[test]
+    fn test_kitty_window_style() {
+        let conf = config::Config {
+            backend: Some(config::Backend::Kitty),
+            window: config::Window {
+                decorations: Some(false),
+                padding: Some(config::Padding { x: 5, y: 2 }),
+                opacity: Some(0.9),
+                ..Default::default()
+            },
+            ..Default::default()
+        };
+        let mut kitty = Kitty {
+            exe_path: PathBuf::new(),
+            temp_file: None,
+        };
+        kitty.create_conf_file(&conf);
+        let result = fs::read_to_string(kitty.temp_file.as_ref().unwrap().path()).unwrap();
+        assert!(result.contains("hide_window_decorations yes\n"));
+        // The vertical padding comes first.
+        assert!(result.contains("window_padding_width 2 5\n"));
+        assert!(result.contains("background_opacity 0.9\n"));
+    }
+
+    #[test]
+    fn test_kitty_cursor_blink_interval() {
+        let blink_interval = |blink: Option<bool>, blink_interval: Option<u32>| {
+            let conf = config::Config {
+                backend: Some(config::Backend::Kitty),
+                cursor: config::Cursor {
+                    shape: Some(config::CursorShape::Beam),
+                    blink,
+                    blink_interval,
+                    ..Default::default()
+                },
+                ..Default::default()
+            };
+            let mut kitty = Kitty {
+                exe_path: PathBuf::new(),
+                temp_file: None,
+            };
+            kitty.create_conf_file(&conf);
+            let result = fs::read_to_string(kitty.temp_file.as_ref().unwrap().path()).unwrap();
+            assert!(result.contains("cursor_shape beam\n"));
+            result
+                .lines()
+                .find_map(|l| l.strip_prefix("cursor_blink_interval "))
+                .unwrap_or_default()
+                .to_string()
+        };
+        assert_eq!(blink_interval(Some(false), Some(750)), "0");
+        assert_eq!(blink_interval(None, Some(750)), "0.75");
+        assert_eq!(blink_interval(Some(true), Some(500)), "0.5");
+        assert_eq!(blink_interval(Some(true), None), "-1");
+        // Nothing is written without the blink options.
+        assert_eq!(blink_interval(None, None), "");
+    }
+
+    #[test]
+    fn test_kitty_symbol_maps() {
+        let fonts: Vec<FontSpec> = vec![
+            "test_font".into(),
+            "Noto Sans CJK JP".into(),
+            "Symbols Nerd Font Mono".into(),
+            config::FontSpec {
+                unicode_ranges: vec!["U+1F300-U+1FAFF".to_string(), "U+2600".to_string()],
+                ..FontSpec::from("Noto Emoji")
+            },
+        ];
+        assert_eq!(
+            Kitty::symbol_maps(&fonts),
+            vec![
+                format!("symbol_map {} Noto Sans CJK JP", CJK_RANGES.join(",")),
+                format!(
+                    "symbol_map {} Symbols Nerd Font Mono",
+                    SYMBOL_RANGES.join(",")
+                ),
+                "symbol_map U+1F300-U+1FAFF,U+2600 Noto Emoji".to_string(),
+            ]
+        );
+        assert!(Kitty::symbol_maps(&fonts[..1]).is_empty());
+    }
+
+    #[test]
+    fn test_kitty_clipboard() {
+        let conf = config::Config {
+            backend: Some(config::Backend::Kitty),
+            clipboard: config::Clipboard {
+                copy: Some("ctrl+shift+c".parse().unwrap()),
+                paste: Some("ctrl+shift+v".parse().unwrap()),
+            },
+            ..Default::default()
+        };
+        let mut kitty = Kitty {
+            exe_path: PathBuf::new(),
+            temp_file: None,
+        };
+        kitty.create_conf_file(&conf);
+        let result = fs::read_to_string(kitty.temp_file.as_ref().unwrap().path()).unwrap();
+        assert!(result.contains("map ctrl+shift+c send_text all \\x1b[99;6u\n"));
+        assert!(result.contains("map ctrl+shift+v send_text all \\x1b[118;6u\n"));
+    }
+}
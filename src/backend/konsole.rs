@@ -0,0 +1,271 @@
+use super::Functions;
+use crate::config::{Config, CursorShape};
+use crate::error::GlrnvimError;
+use ini::Ini;
+use std::io::Write;
+use std::path::PathBuf;
+use tempfile::NamedTempFile;
+
+pub const KONSOLE_NAME: &str = "konsole";
+
+// The default QFont description used by Konsole when there is nothing to inherit from.
+const DEFAULT_FONT: &str = "Monospace,12,-1,5,50,0,0,0,0,0";
+
+struct Konsole {
+    exe_path: PathBuf,
+    temp_file: Option<NamedTempFile>,
+}
+
+pub fn init(config: &Config) -> Result<Box<dyn Functions>, GlrnvimError> {
+    let exe_path = super::exe_path(&config.term_exe_path, KONSOLE_NAME)?;
+
+    Ok(Box::new(Konsole {
+        exe_path,
+        temp_file: None,
+    }))
+}
+
+impl Konsole {
+    fn create_profile(&self, config: &Config) -> Ini {
+        // The settings are applied on top of the given profile, or the default one.
+        let mut profile = if let Some(config_path) = config.term_config_path.as_ref() {
+            Ini::load_from_file(config_path).expect("Failed to load the profile")
+        } else if config.load_term_conf {
+            match Konsole::find_default_profile() {
+                Some(path) => Ini::load_from_file(path).expect("Failed to load default profile"),
+                None => Ini::new(),
+            }
+        } else {
+            Ini::new()
+        };
+
+        profile
+            .with_section(Some("General"))
+            .set("Name", "glrnvim")
+            .set("Parent", "FALLBACK/");
+
+        // Konsole stores the font as a QFont description, e.g. "Monospace,12,-1,5,50,0,0,0,0,0".
+        // Only the family and the point size are replaced.
+        if !config.fonts.is_empty() || config.font_size > 0.0 {
+            let current = profile
+                .get_from(Some("Appearance"), "Font")
+                .unwrap_or(DEFAULT_FONT);
+            let mut font: Vec<String> = current.split(',').map(|s| s.to_string()).collect();
+            if font.len() < 2 {
+                font = DEFAULT_FONT.split(',').map(|s| s.to_string()).collect();
+            }
+            if let Some(family) = config.fonts.first() {
+                // Konsole doesn't support fallback fonts. Just use the first chosen font.
+                font[0] = family.to_string();
+            }
+            if config.font_size > 0.0 {
+                font[1] = config.font_size.to_string();
+            }
+            profile
+                .with_section(Some("Appearance"))
+                .set("Font", font.join(","));
+        }
+
+        // Konsole doesn't support the letter spacing.
+        if let Some(line_height) = config.line_height {
+            if line_height < 0 {
+                log::warn!("Negative line_height is not supported by konsole. 0 is used instead.");
+            }
+            profile
+                .with_section(Some("Appearance"))
+                .set("LineSpacing", line_height.max(0).to_string());
+        }
+
+        if let Some((columns, lines)) = config.window.size() {
+            profile
+                .with_section(Some("General"))
+                .set("TerminalColumns", columns.to_string())
+                .set("TerminalRows", lines.to_string());
+        }
+
+        if let Some(padding) = config.window.padding {
+            // Only one margin for both directions.
+            profile
+                .with_section(Some("General"))
+                .set("TerminalMargin", padding.x.to_string());
+        }
+
+        if let Some(shape) = config.cursor.shape {
+            let shape = match shape {
+                CursorShape::Block => "0",
+                CursorShape::Beam => "1",
+                CursorShape::Underline => "2",
+            };
+            profile
+                .with_section(Some("Cursor Options"))
+                .set("CursorShape", shape);
+        }
+        if let Some(blink) = config.cursor.blink {
+            profile
+                .with_section(Some("Terminal Features"))
+                .set("BlinkingCursorEnabled", blink.to_string());
+        }
+
+        // Hide the scrollbar.
+        profile
+            .with_section(Some("Scrolling"))
+            .set("ScrollBarPosition", "2");
+
+        profile
+    }
+
+    fn create_profile_file(&mut self, config: &Config) {
+        let profile = self.create_profile(config);
+
+        // Konsole only accepts profile files with the ".profile" suffix.
+        let mut file = tempfile::Builder::new()
+            .suffix(".profile")
+            .tempfile()
+            .expect("Failed to create temporary file");
+        profile
+            .write_to(&mut file)
+            .expect("Failed to write to temporary file");
+        file.flush().unwrap();
+
+        self.temp_file = Some(file);
+    }
+
+    // The default profile is specified in konsolerc, and stored in the konsole data directory.
+    fn find_default_profile() -> Option<String> {
+        let base_confs: [String; 0] = [];
+        let pri_confs: [String; 2] = [
+            "$XDG_CONFIG_HOME/konsolerc".to_string(),
+            "$HOME/.config/konsolerc".to_string(),
+        ];
+        let konsolerc = super::find_term_conf_files(&base_confs, &pri_confs);
+        let konsolerc = Ini::load_from_file(konsolerc.first()?).ok()?;
+        let profile_name = konsolerc.get_from(Some("Desktop Entry"), "DefaultProfile")?;
+
+        let pri_profiles: [String; 2] = [
+            format!("$XDG_DATA_HOME/konsole/{}", profile_name),
+            format!("$HOME/.local/share/konsole/{}", profile_name),
+        ];
+        super::find_term_conf_files(&base_confs, &pri_profiles)
+            .first()
+            .cloned()
+    }
+}
+
+impl Functions for Konsole {
+    fn create_command(&mut self, config: &Config) -> std::process::Command {
+        let mut command = std::process::Command::new(&self.exe_path);
+
+        self.create_profile_file(config);
+        command.arg("--profile");
+        command.arg(self.temp_file.as_ref().unwrap().path());
+
+        // Don't merge the window into an existing Konsole instance.
+        command.arg("--separate");
+        command.arg("--hide-tabbar");
+        command.arg("--hide-menubar");
+        if config.window.fullscreen {
+            command.arg("--fullscreen");
+        }
+
+        if let Ok(current_dir) = std::env::current_dir() {
+            command.arg("--workdir");
+            command.arg(current_dir);
+        }
+
+        command.arg("-e");
+        command.arg(&config.nvim_exe_path);
+        command.args(super::common_args(config));
+
+        command
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config;
+
+    fn profile_str(profile: &Ini) -> String {
+        let mut buf: Vec<u8> = Vec::new();
+        profile.write_to(&mut buf).unwrap();
+        String::from_utf8(buf).unwrap()
+    }
+
+    #[test]
+    fn test_create_konsole_profile() {
+        let conf = config::Config {
+            backend: Some(config::Backend::Konsole),
+            font_size: 14.0,
+            fonts: vec!["test_font".into(), "fallback_font".into()],
+            ..Default::default()
+        };
+        let konsole = Konsole {
+            exe_path: PathBuf::new(),
+            temp_file: None,
+        };
+        let expected = r#"[General]
+Name=glrnvim
+Parent=FALLBACK/
+
+[Appearance]
+Font=test_font,14,-1,5,50,0,0,0,0,0
+
+[Scrolling]
+ScrollBarPosition=2
+"#;
+        assert_eq!(profile_str(&konsole.create_profile(&conf)), expected);
+    }
+
+    #[test]
+    fn test_create_konsole_profile_without_font() {
+        let conf = config::Config {
+            backend: Some(config::Backend::Konsole),
+            ..Default::default()
+        };
+        let konsole = Konsole {
+            exe_path: PathBuf::new(),
+            temp_file: None,
+        };
+        let expected = r#"[General]
+Name=glrnvim
+Parent=FALLBACK/
+
+[Scrolling]
+ScrollBarPosition=2
+"#;
+        assert_eq!(profile_str(&konsole.create_profile(&conf)), expected);
+    }
+
+    #[test]
+    fn test_create_konsole_profile_with_term_config_path() {
+        let mut term_conf = NamedTempFile::new().unwrap();
+        write!(
+            term_conf,
+            "[General]\nName=user\nCommand=zsh\n\n[Appearance]\nColorScheme=Breeze\n"
+        )
+        .unwrap();
+        let conf = config::Config {
+            backend: Some(config::Backend::Konsole),
+            term_config_path: Some(term_conf.path().display().to_string()),
+            font_size: 14.0,
+            ..Default::default()
+        };
+        let konsole = Konsole {
+            exe_path: PathBuf::new(),
+            temp_file: None,
+        };
+        let expected = r#"[General]
+Command=zsh
+Name=glrnvim
+Parent=FALLBACK/
+
+[Appearance]
+ColorScheme=Breeze
+Font=Monospace,14,-1,5,50,0,0,0,0,0
+
+[Scrolling]
+ScrollBarPosition=2
+"#;
+        assert_eq!(profile_str(&konsole.create_profile(&conf)), expected);
+    }
+}
@@ -1,17 +1,27 @@
 mod alacritty;
+mod contour;
+mod custom;
 mod foot;
+mod ghostty;
 mod kitty;
+mod konsole;
+mod rio;
+mod st;
 mod urxvt;
+mod vte;
 mod wezterm;
+mod xterm;
 use super::config::Config;
-use crate::config::Backend;
+use crate::config::{Backend, KeyChord, Window};
 use crate::error::GlrnvimError;
 use std::path::PathBuf;
 use sysinfo::Pid;
+use toml_edit::{DocumentMut, Table};
 
 extern crate shellexpand;
 
 pub trait Functions {
+    fn pre_start(&mut self, _config: &Config) {}
     fn create_command(&mut self, config: &Config) -> std::process::Command;
     fn post_start(&mut self, _config: &Config, _term_pid: Pid) {}
 }
@@ -35,19 +45,113 @@ const COMMON_ARGS: &[&str] = &[
     "\"let g:glrnvim_gui=1\"",
 ];
 
+// The step in points of the font zoom keys, for the terminals which can set it.
+const FONT_SIZE_STEP: f64 = 1.0;
+
+// COMMON_ARGS with the settings depending on the config.
+fn common_args(config: &Config) -> Vec<String> {
+    let mut cmds: Vec<String> = Vec::new();
+    if config.font_size > 0.0 {
+        cmds.push(format!("let g:glrnvim_font_size={}", config.font_size));
+    }
+    // The clipboard keys are sent in the CSI u encoding by the terminal. nvim takes up to 10 --cmd
+    // arguments, so the mappings are joined in one.
+    let mut maps: Vec<String> = Vec::new();
+    if let Some(copy) = config.clipboard.copy.as_ref() {
+        maps.push(format!("xnoremap {} \"+y", vim_key(copy)));
+    }
+    if let Some(paste) = config.clipboard.paste.as_ref() {
+        let key = vim_key(paste);
+        maps.push(format!("nnoremap {} \"+p", key));
+        maps.push(format!("xnoremap {} \"+P", key));
+        maps.push(format!("inoremap {} <C-r><C-o>+", key));
+        maps.push(format!("cnoremap {} <C-r>+", key));
+    }
+    if !maps.is_empty() {
+        cmds.push(maps.join("|"));
+    }
+
+    let mut args: Vec<String> = COMMON_ARGS.iter().map(|s| s.to_string()).collect();
+    for cmd in cmds {
+        if cfg!(target_os = "windows") {
+            args.push("\"--cmd\"".to_string());
+            args.push(format!("\"{}\"", cmd.replace('"', "\\\"")));
+        } else {
+            args.push("--cmd".to_string());
+            args.push(cmd);
+        }
+    }
+    args
+}
+
+// The names of the 8 ANSI colors used by some terminals' configs.
+pub const ANSI_COLOR_NAMES: [&str; 8] = [
+    "black", "red", "green", "yellow", "blue", "magenta", "cyan", "white",
+];
+
+// The order to probe the terminals when no backend is specified.
+const DEFAULT_BACKEND_ORDER: &[Backend] = &[
+    Backend::Alacritty,
+    Backend::Urxvt,
+    Backend::Kitty,
+    Backend::Wezterm,
+    Backend::Foot,
+    Backend::Ghostty,
+    Backend::Rio,
+    Backend::Contour,
+    Backend::St,
+    Backend::Xterm,
+    Backend::Konsole,
+    Backend::GnomeTerminal,
+    Backend::Ptyxis,
+    Backend::Kgx,
+];
+
+// Same as above, but prefer the Wayland native terminals in a Wayland session.
+const WAYLAND_BACKEND_ORDER: &[Backend] = &[
+    Backend::Foot,
+    Backend::Alacritty,
+    Backend::Kitty,
+    Backend::Wezterm,
+    Backend::Ghostty,
+    Backend::Rio,
+    Backend::Contour,
+    Backend::Konsole,
+    Backend::GnomeTerminal,
+    Backend::Ptyxis,
+    Backend::Kgx,
+    Backend::Urxvt,
+    Backend::St,
+    Backend::Xterm,
+];
+
 pub fn init(config: &Config) -> Result<Box<dyn Functions>, GlrnvimError> {
+    let display = DisplayServers::detect();
     match &config.backend {
-        Some(backend) => match backend {
-            Backend::Alacritty => alacritty::init(config),
-            Backend::Urxvt => urxvt::init(config),
-            Backend::Kitty => kitty::init(config),
-            Backend::Wezterm => wezterm::init(config),
-            Backend::Foot => foot::init(config),
-        },
+        Some(backend) => {
+            if !display.supports(backend) {
+                log::warn!(
+                    "Backend {:?} may not be able to run on the current display server.",
+                    backend
+                );
+            }
+            init_backend(backend, config)
+        }
         None => {
-            for init_func in &[alacritty::init, urxvt::init, kitty::init, wezterm::init] {
-                if let Ok(functions) = init_func(config) {
-                    return Ok(functions);
+            for backend in backend_order(config, &display) {
+                if !display.supports(backend) {
+                    log::debug!(
+                        "Skip backend {:?}. It cannot run on the current display server.",
+                        backend
+                    );
+                    continue;
+                }
+                match init_backend(backend, config) {
+                    Ok(functions) => {
+                        log::debug!("Use backend: {:?}.", backend);
+                        return Ok(functions);
+                    }
+                    Err(e) => log::debug!("Skip backend {:?}. {}", backend, e),
                 }
             }
 
@@ -58,6 +162,66 @@ pub fn init(config: &Config) -> Result<Box<dyn Functions>, GlrnvimError> {
     }
 }
 
+fn init_backend(backend: &Backend, config: &Config) -> Result<Box<dyn Functions>, GlrnvimError> {
+    match backend {
+        Backend::Alacritty => alacritty::init(config),
+        Backend::Urxvt => urxvt::init(config),
+        Backend::Kitty => kitty::init(config),
+        Backend::Wezterm => wezterm::init(config),
+        Backend::Foot => foot::init(config),
+        Backend::Ghostty => ghostty::init(config),
+        Backend::St => st::init(config),
+        Backend::Xterm => xterm::init(config),
+        Backend::Konsole => konsole::init(config),
+        Backend::GnomeTerminal => vte::init_gnome_terminal(config),
+        Backend::Kgx => vte::init_kgx(config),
+        Backend::Ptyxis => vte::init_ptyxis(config),
+        Backend::Rio => rio::init(config),
+        Backend::Contour => contour::init(config),
+        Backend::Custom => custom::init(config),
+    }
+}
+
+fn backend_order<'a>(config: &'a Config, display: &DisplayServers) -> &'a [Backend] {
+    if !config.backend_preference.is_empty() {
+        &config.backend_preference
+    } else if display.wayland {
+        WAYLAND_BACKEND_ORDER
+    } else {
+        DEFAULT_BACKEND_ORDER
+    }
+}
+
+// The display servers available to the current session. Both are false if glrnvim is not
+// running under a Wayland or X11 session, e.g. on MacOS or Windows.
+struct DisplayServers {
+    wayland: bool,
+    // X11 or Xwayland
+    x11: bool,
+}
+
+impl DisplayServers {
+    fn detect() -> Self {
+        let is_set = |name: &str| std::env::var_os(name).is_some_and(|v| !v.is_empty());
+        DisplayServers {
+            wayland: is_set("WAYLAND_DISPLAY")
+                || std::env::var("XDG_SESSION_TYPE").is_ok_and(|v| v == "wayland"),
+            x11: is_set("DISPLAY"),
+        }
+    }
+
+    // Check if the backend can run on the current display servers.
+    fn supports(&self, backend: &Backend) -> bool {
+        match backend {
+            // X11 only
+            Backend::Urxvt | Backend::St | Backend::Xterm => self.x11 || !self.wayland,
+            // Wayland only
+            Backend::Foot => self.wayland || !self.x11,
+            _ => true,
+        }
+    }
+}
+
 fn exe_path(exe_path: &Option<String>, exe_name: &str) -> Result<PathBuf, GlrnvimError> {
     let exe_name = match exe_path {
         Some(exe_path) => PathBuf::from(exe_path),
@@ -130,3 +294,229 @@ fn find_term_conf_files(base_confs: &[String], priority_confs: &[String]) -> Vec
 
     ret
 }
+
+// Load the given toml config file, or the first existing one of the priority config files.
+fn load_toml_conf(path: Option<String>, priority_confs: &[String]) -> DocumentMut {
+    let conf_path = path.or({
+        let base_confs: [String; 0] = [];
+        let confs = find_term_conf_files(&base_confs, priority_confs);
+        if confs.is_empty() {
+            None
+        } else {
+            Some(confs[0].to_string())
+        }
+    });
+    match conf_path {
+        Some(p) => {
+            let content = std::fs::read_to_string(p.clone())
+                .unwrap_or_else(|_| panic!("Cannot load term config file: '{}'", p));
+            match content.parse::<DocumentMut>() {
+                Ok(mapping) => mapping,
+                Err(msg) => {
+                    log::warn!("Cannot parse term config file '{}'. {}", p, msg);
+                    DocumentMut::new()
+                }
+            }
+        }
+        _ => DocumentMut::new(),
+    }
+}
+
+// Get the child table with the given key. Create it if it doesn't exist.
+fn toml_table<'a>(parent: &'a mut Table, key: &str) -> &'a mut Table {
+    if !parent.get(key).is_some_and(|v| v.is_table()) {
+        let mut table = Table::new();
+        table.set_implicit(true);
+        parent.insert(key, toml_edit::Item::Table(table));
+    }
+    parent.get_mut(key).unwrap().as_table_mut().unwrap()
+}
+
+// The X11 geometry string, e.g. "80x24+10+20".
+fn x11_geometry(window: &Window) -> Option<String> {
+    let mut geometry = String::new();
+    if let Some((columns, lines)) = window.size() {
+        geometry.push_str(format!("{}x{}", columns, lines).as_str());
+    }
+    if let Some(position) = window.position {
+        geometry.push_str(format!("{:+}{:+}", position.x, position.y).as_str());
+    }
+    if geometry.is_empty() {
+        None
+    } else {
+        Some(geometry)
+    }
+}
+
+// The modifiers of the key in the given names, by the order of ctrl, shift, alt and super.
+fn key_mods<'a>(key: &KeyChord, names: [&'a str; 4]) -> Vec<&'a str> {
+    [key.ctrl, key.shift, key.alt, key.super_key]
+        .into_iter()
+        .zip(names)
+        .filter(|(m, _)| *m)
+        .map(|(_, name)| name)
+        .collect()
+}
+
+// The X11 keysym name of the key, e.g. "Prior" for page_up. Letters are in lowercase.
+fn x11_keysym(key: &str) -> String {
+    let name = match key {
+        "tab" => "Tab",
+        "enter" => "Return",
+        "escape" => "Escape",
+        "space" => "space",
+        "backspace" => "BackSpace",
+        "delete" => "Delete",
+        "insert" => "Insert",
+        "home" => "Home",
+        "end" => "End",
+        "page_up" => "Prior",
+        "page_down" => "Next",
+        "up" => "Up",
+        "down" => "Down",
+        "left" => "Left",
+        "right" => "Right",
+        "plus" => "plus",
+        "minus" => "minus",
+        "equal" => "equal",
+        f if f.len() > 1 && f.starts_with('f') => return f.to_uppercase(),
+        k => k,
+    };
+    name.to_string()
+}
+
+// The parameters of the CSI u sequence of a letter or digit key, e.g. "99;6u" for ctrl+shift+c.
+fn csi_u(key: &KeyChord) -> String {
+    let code = key.key.chars().next().map_or(0, |c| c as u32);
+    let mods =
+        1 + key.shift as u8 + 2 * key.alt as u8 + 4 * key.ctrl as u8 + 8 * key.super_key as u8;
+    format!("{};{}u", code, mods)
+}
+
+// The key in the vim notation, e.g. "<C-S-c>".
+fn vim_key(key: &KeyChord) -> String {
+    let mods = key_mods(key, ["C-", "S-", "M-", "D-"]);
+    format!("<{}{}>", mods.join(""), key.key)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_display_servers_supports() {
+        let wayland_only = DisplayServers {
+            wayland: true,
+            x11: false,
+        };
+        assert!(!wayland_only.supports(&Backend::Urxvt));
+        assert!(!wayland_only.supports(&Backend::St));
+        assert!(!wayland_only.supports(&Backend::Xterm));
+        assert!(wayland_only.supports(&Backend::Foot));
+        assert!(wayland_only.supports(&Backend::Alacritty));
+
+        let x11_only = DisplayServers {
+            wayland: false,
+            x11: true,
+        };
+        assert!(x11_only.supports(&Backend::Urxvt));
+        assert!(!x11_only.supports(&Backend::Foot));
+        assert!(x11_only.supports(&Backend::Kitty));
+
+        let xwayland = DisplayServers {
+            wayland: true,
+            x11: true,
+        };
+        assert!(xwayland.supports(&Backend::Urxvt));
+        assert!(xwayland.supports(&Backend::Foot));
+
+        let unknown = DisplayServers {
+            wayland: false,
+            x11: false,
+        };
+        assert!(unknown.supports(&Backend::Urxvt));
+        assert!(unknown.supports(&Backend::Foot));
+    }
+
+    #[test]
+    fn test_x11_geometry() {
+        let mut window = Window::default();
+        assert_eq!(x11_geometry(&window), None);
+        window.columns = Some(80);
+        window.lines = Some(24);
+        assert_eq!(x11_geometry(&window), Some("80x24".to_string()));
+        window.position = Some(crate::config::Position { x: 10, y: 20 });
+        assert_eq!(x11_geometry(&window), Some("80x24+10+20".to_string()));
+        window.columns = None;
+        window.lines = None;
+        assert_eq!(x11_geometry(&window), Some("+10+20".to_string()));
+    }
+
+    #[test]
+    fn test_key_helpers() {
+        let key: KeyChord = "ctrl+alt+page_up".parse().unwrap();
+        assert_eq!(key_mods(&key, ["C", "S", "M", "4"]), vec!["C", "M"]);
+        assert_eq!(x11_keysym(&key.key), "Prior");
+        assert_eq!(x11_keysym("f11"), "F11");
+        assert_eq!(x11_keysym("z"), "z");
+
+        let key: KeyChord = "ctrl+shift+c".parse().unwrap();
+        assert_eq!(csi_u(&key), "99;6u");
+        assert_eq!(vim_key(&key), "<C-S-c>");
+        let key: KeyChord = "alt+super+1".parse().unwrap();
+        assert_eq!(csi_u(&key), "49;11u");
+        assert_eq!(vim_key(&key), "<M-D-1>");
+    }
+
+    #[test]
+    #[cfg(not(target_os = "windows"))]
+    fn test_common_args() {
+        let config = Config {
+            font_size: 11.5,
+            ..Default::default()
+        };
+        let args = common_args(&config);
+        assert_eq!(args.len(), COMMON_ARGS.len() + 2);
+        assert_eq!(args[args.len() - 1], "let g:glrnvim_font_size=11.5");
+        assert_eq!(common_args(&Config::default()), COMMON_ARGS);
+
+        let config = Config {
+            clipboard: crate::config::Clipboard {
+                copy: Some("ctrl+shift+c".parse().unwrap()),
+                paste: Some("ctrl+shift+v".parse().unwrap()),
+            },
+            ..Default::default()
+        };
+        let args = common_args(&config);
+        assert_eq!(args.len(), COMMON_ARGS.len() + 2);
+        assert_eq!(
+            args[args.len() - 1],
+            "xnoremap <C-S-c> \"+y|nnoremap <C-S-v> \"+p|xnoremap <C-S-v> \"+P|\
+             inoremap <C-S-v> <C-r><C-o>+|cnoremap <C-S-v> <C-r>+"
+        );
+    }
+
+    #[test]
+    fn test_backend_order() {
+        let wayland = DisplayServers {
+            wayland: true,
+            x11: false,
+        };
+        let x11 = DisplayServers {
+            wayland: false,
+            x11: true,
+        };
+        let config = Config::default();
+        assert_eq!(backend_order(&config, &wayland)[0], Backend::Foot);
+        assert_eq!(backend_order(&config, &x11)[0], Backend::Alacritty);
+
+        let config = Config {
+            backend_preference: vec![Backend::Kitty, Backend::Foot],
+            ..Default::default()
+        };
+        assert_eq!(
+            backend_order(&config, &wayland),
+            &[Backend::Kitty, Backend::Foot]
+        );
+    }
+}
@@ -0,0 +1,519 @@
+use super::Functions;
+use crate::config::{Colors, Config, Cursor, CursorShape, KeyAction, KeyBinding, Window};
+use crate::error::GlrnvimError;
+use std::fs;
+use std::path::{Path, PathBuf};
+use tempfile::TempDir;
+use toml_edit::{DocumentMut, InlineTable, Item, Table, Value, value};
+
+pub const RIO_NAME: &str = "rio";
+
+// The directories where rio looks up the themes by default.
+const THEME_DIRS: [&str; 3] = [
+    "$RIO_CONFIG_HOME/themes",
+    "$XDG_CONFIG_HOME/rio/themes",
+    "$HOME/.config/rio/themes",
+];
+
+struct Rio {
+    exe_path: PathBuf,
+    // Rio loads config.toml from the $RIO_CONFIG_HOME directory.
+    cfg_dir: Option<TempDir>,
+}
+
+pub fn init(config: &Config) -> Result<Box<dyn Functions>, GlrnvimError> {
+    let exe_path = super::exe_path(&config.term_exe_path, RIO_NAME)?;
+
+    Ok(Box::new(Rio {
+        exe_path,
+        cfg_dir: None,
+    }))
+}
+
+impl Rio {
+    fn create_base_conf(&mut self, config: &Config) -> DocumentMut {
+        config.term_config_path.as_ref().map_or_else(
+            {
+                || match config.load_term_conf {
+                    true => Rio::load_rio_conf(None),
+                    _ => DocumentMut::new(),
+                }
+            },
+            |p| Rio::load_rio_conf(Some(p.to_string())),
+        )
+    }
+
+    fn create_conf_file(&mut self, base_mapping: &mut DocumentMut, config: &Config) {
+        let key_fonts = "fonts";
+        if !base_mapping.contains_key(key_fonts) {
+            // Try to merge the terminal settings
+            let v = Table::new();
+            base_mapping[key_fonts] = Item::Table(v);
+        }
+        // Set the font size
+        let fonts_mapping = base_mapping
+            .get_mut(key_fonts)
+            .unwrap()
+            .as_table_mut()
+            .unwrap();
+        if config.font_size > 0.0 {
+            fonts_mapping.insert("size", value(config.font_size));
+        }
+        // Set the font and the fallback fonts
+        if let Some((first, rest)) = config.fonts.split_first() {
+            let mut regular_mapping = Table::new();
+            regular_mapping.insert("family", value(first.to_string()));
+            fonts_mapping.insert("regular", Item::Table(regular_mapping));
+
+            if !rest.is_empty() {
+                let mut extras = toml_edit::Array::new();
+                for font in rest {
+                    let mut extra = toml_edit::InlineTable::new();
+                    extra.insert("family", Value::from(font.to_string()));
+                    extras.push(extra);
+                }
+                fonts_mapping.insert("extras", value(extras));
+            }
+        }
+        // Only overwrite the font setting if it has been set in the glrnvim config
+        if fonts_mapping.is_empty() {
+            base_mapping.remove(key_fonts);
+        }
+
+        Rio::set_colors(base_mapping, &config.colors);
+
+        Rio::set_window(base_mapping, &config.window);
+        Rio::set_cursor(base_mapping, &config.cursor);
+
+        // No tabs
+        let mut navigation = Table::new();
+        navigation.insert("mode", value("Plain"));
+        base_mapping["navigation"] = Item::Table(navigation);
+
+        Rio::set_key_bindings(base_mapping, config);
+
+        let toml_str = base_mapping.to_string();
+
+        // Write to a temp dir to be loaded by rio
+        let dir = tempfile::tempdir().unwrap();
+        fs::write(dir.path().join("config.toml"), toml_str.as_bytes()).unwrap();
+        // The themes of the user's config are looked up in the temp dir as well.
+        if config.load_term_conf || config.term_config_path.is_some() {
+            let theme_dirs: Vec<String> = THEME_DIRS
+                .iter()
+                .map(|d| shellexpand::full(d).unwrap_or_default().to_string())
+                .collect();
+            Rio::copy_themes(dir.path(), &theme_dirs);
+        }
+
+        self.cfg_dir = Some(dir);
+    }
+
+    // Copy the themes in the first existing one of the theme dirs to the config dir.
+    fn copy_themes(cfg_dir: &Path, theme_dirs: &[String]) {
+        let Some(themes) = theme_dirs
+            .iter()
+            .map(Path::new)
+            .find(|d| !d.as_os_str().is_empty() && d.is_dir())
+        else {
+            return;
+        };
+        let target = cfg_dir.join("themes");
+        let result = fs::create_dir(&target).and_then(|_| {
+            for entry in fs::read_dir(themes)? {
+                let path = entry?.path();
+                if let Some(name) = path.file_name().filter(|_| path.is_file()) {
+                    fs::copy(&path, target.join(name))?;
+                }
+            }
+            Ok(())
+        });
+        if let Err(e) = result {
+            log::warn!(
+                "Failed to copy the rio themes in '{}': {}",
+                themes.display(),
+                e
+            );
+        }
+    }
+
+    // The key bindings of the user's config are kept, unless glrnvim binds the same keys.
+    fn set_key_bindings(base_mapping: &mut DocumentMut, config: &Config) {
+        let keys: Vec<InlineTable> = config.key_bindings().iter().map(Rio::key_binding).collect();
+        let bindings = super::toml_table(base_mapping, "bindings");
+        let user_keys: Vec<InlineTable> = match bindings.get("keys") {
+            Some(Item::Value(Value::Array(array))) => array
+                .iter()
+                .filter_map(|v| v.as_inline_table().cloned())
+                .collect(),
+            Some(Item::ArrayOfTables(array)) => array
+                .iter()
+                .map(|t| t.clone().into_inline_table())
+                .collect(),
+            _ => Vec::new(),
+        };
+        let mut merged = toml_edit::Array::new();
+        for key in user_keys {
+            if !keys
+                .iter()
+                .any(|k| Rio::key_chord(k) == Rio::key_chord(&key))
+            {
+                merged.push(key);
+            }
+        }
+        for key in keys {
+            merged.push(key);
+        }
+        bindings["keys"] = value(merged);
+    }
+
+    // The key and the sorted modifiers of a binding, e.g. ("v", ["control", "shift"]).
+    fn key_chord(binding: &InlineTable) -> (String, Vec<String>) {
+        let text = |k: &str| {
+            binding
+                .get(k)
+                .and_then(|v| v.as_str())
+                .unwrap_or_default()
+                .to_lowercase()
+        };
+        let mut mods: Vec<String> = text("with")
+            .split('|')
+            .map(|m| m.trim().to_string())
+            .filter(|m| !m.is_empty())
+            .collect();
+        mods.sort();
+        (text("key"), mods)
+    }
+
+    fn key_binding(binding: &KeyBinding) -> InlineTable {
+        let key = match binding.key.key.as_str() {
+            "escape" => "esc",
+            "backspace" => "back",
+            "page_up" => "pageup",
+            "page_down" => "pagedown",
+            "plus" => "+",
+            "minus" => "-",
+            "equal" => "=",
+            k => k,
+        };
+        let action = match binding.action {
+            KeyAction::Unbind => "None",
+            KeyAction::Passthrough => "ReceiveChar",
+            // Sent as text instead of an action.
+            KeyAction::CsiU => "",
+            KeyAction::Copy => "Copy",
+            KeyAction::Paste => "Paste",
+            KeyAction::IncreaseFontSize => "IncreaseFontSize",
+            KeyAction::DecreaseFontSize => "DecreaseFontSize",
+            KeyAction::ResetFontSize => "ResetFontSize",
+            KeyAction::ToggleFullscreen => "ToggleFullscreen",
+        };
+        let mods = super::key_mods(&binding.key, ["control", "shift", "alt", "super"]);
+
+        let mut table = InlineTable::new();
+        table.insert("key", Value::from(key));
+        if !mods.is_empty() {
+            table.insert("with", Value::from(mods.join(" | ")));
+        }
+        if binding.action == KeyAction::CsiU {
+            let text = format!("\x1b[{}", super::csi_u(&binding.key));
+            table.insert("text", Value::from(text));
+        } else {
+            table.insert("action", Value::from(action));
+        }
+        table
+    }
+
+    fn set_colors(base_mapping: &mut DocumentMut, colors: &Colors) {
+        if colors.is_empty() {
+            return;
+        }
+        let colors_mapping = super::toml_table(base_mapping, "colors");
+        for (key, color) in [
+            ("foreground", &colors.foreground),
+            ("background", &colors.background),
+            ("cursor", &colors.cursor),
+            ("selection-foreground", &colors.selection_foreground),
+            ("selection-background", &colors.selection_background),
+        ] {
+            if let Some(c) = color {
+                colors_mapping.insert(key, value(c.to_string()));
+            }
+        }
+        for (i, c) in colors.palette.iter().enumerate() {
+            let key = if i < 8 {
+                super::ANSI_COLOR_NAMES[i].to_string()
+            } else {
+                format!("light-{}", super::ANSI_COLOR_NAMES[i - 8])
+            };
+            colors_mapping.insert(&key, value(c.to_string()));
+        }
+    }
+
+    fn set_window(base_mapping: &mut DocumentMut, window: &Window) {
+        if let Some(padding) = window.padding {
+            base_mapping.insert("padding-x", value(i64::from(padding.x)));
+            let mut padding_y = toml_edit::Array::new();
+            padding_y.push(i64::from(padding.y));
+            padding_y.push(i64::from(padding.y));
+            base_mapping.insert("padding-y", value(padding_y));
+        }
+
+        // Rio only takes the window size in pixels, and cannot set the window position.
+        if !(window.fullscreen
+            || window.maximized
+            || window.decorations.is_some()
+            || window.opacity.is_some())
+        {
+            return;
+        }
+        let window_mapping = super::toml_table(base_mapping, "window");
+        if window.fullscreen {
+            window_mapping.insert("mode", value("Fullscreen"));
+        } else if window.maximized {
+            window_mapping.insert("mode", value("Maximized"));
+        }
+        if let Some(decorations) = window.decorations {
+            let decorations = if decorations { "Enabled" } else { "Disabled" };
+            window_mapping.insert("decorations", value(decorations));
+        }
+        if let Some(opacity) = window.opacity {
+            window_mapping.insert("opacity", value(f64::from(opacity)));
+        }
+    }
+
+    fn set_cursor(base_mapping: &mut DocumentMut, cursor: &Cursor) {
+        if !cursor.has_style() {
+            return;
+        }
+        let cursor_mapping = super::toml_table(base_mapping, "cursor");
+        if let Some(shape) = cursor.shape {
+            let shape = match shape {
+                CursorShape::Block => "block",
+                CursorShape::Beam => "beam",
+                CursorShape::Underline => "underline",
+            };
+            cursor_mapping.insert("shape", value(shape));
+        }
+        if let Some(blink) = cursor.blink {
+            cursor_mapping.insert("blinking", value(blink));
+        }
+        if let Some(interval) = cursor.blink_interval {
+            cursor_mapping.insert("blinking-interval", value(i64::from(interval)));
+        }
+    }
+
+    // Load the default rio config
+    fn load_rio_conf(path: Option<String>) -> DocumentMut {
+        let pri_confs: [String; 3] = [
+            "$RIO_CONFIG_HOME/config.toml".to_string(),
+            "$XDG_CONFIG_HOME/rio/config.toml".to_string(),
+            "$HOME/.config/rio/config.toml".to_string(),
+        ];
+        super::load_toml_conf(path, &pri_confs)
+    }
+}
+
+impl Functions for Rio {
+    fn create_command(&mut self, config: &Config) -> std::process::Command {
+        let mut doc = self.create_base_conf(config);
+
+        self.create_conf_file(&mut doc, config);
+        let mut command = std::process::Command::new(&self.exe_path);
+        command.env("RIO_CONFIG_HOME", self.cfg_dir.as_ref().unwrap().path());
+
+        if let Ok(current_dir) = std::env::current_dir() {
+            command.arg("--working-dir");
+            command.arg(current_dir);
+        }
+
+        command.arg("-e");
+        command.arg(&config.nvim_exe_path);
+        command.args(super::common_args(config));
+
+        command
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config;
+
+    #[test]
+    fn test_create_basic_rio_conf() {
+        let conf = config::Config {
+            backend: Some(config::Backend::Rio),
+            font_size: 14.0,
+            fonts: vec!["test_font".into(), "fallback_font".into()],
+            ..Default::default()
+        };
+        let mut rio = Rio {
+            exe_path: PathBuf::new(),
+            cfg_dir: None,
+        };
+        rio.create_conf_file(&mut DocumentMut::new(), &conf);
+        let cfg_dir = rio.cfg_dir;
+        assert!(cfg_dir.is_some());
+        let result = fs::read_to_string(cfg_dir.as_ref().unwrap().path().join("config.toml"));
+        assert!(result.is_ok());
+        let expected = r#"[fonts]
+size = 14.0
+extras = [{ family = "fallback_font" }]
+
+[fonts.regular]
+family = "test_font"
+
+[navigation]
+mode = "Plain"
+
+[bindings]
+keys = [{ key = "z", with = "control", action = "None" }, { key = "=", with = "control", action = "IncreaseFontSize" }, { key = "+", with = "control", action = "IncreaseFontSize" }, { key = "-", with = "control", action = "DecreaseFontSize" }, { key = "0", with = "control", action = "ResetFontSize" }]
+"#;
+        assert_eq!(result.unwrap_or_default(), expected)
+    }
+
+    #[test]
+    fn test_rio_colors() {
+        let mut palette = vec!["#000000".parse().unwrap(); 16];
+        palette[8] = "#666666".parse().unwrap();
+        let conf = config::Config {
+            backend: Some(config::Backend::Rio),
+            colors: config::Colors {
+                foreground: Some("#c5c8c6".parse().unwrap()),
+                selection_background: Some("#373b41".parse().unwrap()),
+                palette,
+                ..Default::default()
+            },
+            ..Default::default()
+        };
+        let mut rio = Rio {
+            exe_path: PathBuf::new(),
+            cfg_dir: None,
+        };
+        rio.create_conf_file(&mut DocumentMut::new(), &conf);
+        let cfg_dir = rio.cfg_dir;
+        let result = fs::read_to_string(cfg_dir.as_ref().unwrap().path().join("config.toml"));
+        let expected = r##"[colors]
+foreground = "#c5c8c6"
+selection-background = "#373b41"
+black = "#000000"
+red = "#000000"
+green = "#000000"
+yellow = "#000000"
+blue = "#000000"
+magenta = "#000000"
+cyan = "#000000"
+white = "#000000"
+light-black = "#666666"
+light-red = "#000000"
+light-green = "#000000"
+light-yellow = "#000000"
+light-blue = "#000000"
+light-magenta = "#000000"
+light-cyan = "#000000"
+light-white = "#000000"
+
+[navigation]
+mode = "Plain"
+
+[bindings]
+keys = [{ key = "z", with = "control", action = "None" }, { key = "=", with = "control", action = "IncreaseFontSize" }, { key = "+", with = "control", action = "IncreaseFontSize" }, { key = "-", with = "control", action = "DecreaseFontSize" }, { key = "0", with = "control", action = "ResetFontSize" }]
+"##;
+        assert_eq!(result.unwrap_or_default(), expected)
+    }
+
+    #[test]
+    fn test_not_overwrite_rio_conf() {
+        let mut term_conf = DocumentMut::new();
+        let mut fonts_mapping = Table::new();
+        fonts_mapping.insert("size", value(42));
+        term_conf.insert("fonts", Item::Table(fonts_mapping));
+
+        let conf = config::Config {
+            backend: Some(config::Backend::Rio),
+            ..Default::default()
+        };
+        let mut rio = Rio {
+            exe_path: PathBuf::new(),
+            cfg_dir: None,
+        };
+        rio.create_conf_file(&mut term_conf, &conf);
+        let cfg_dir = rio.cfg_dir;
+        assert!(cfg_dir.is_some());
+        let result = fs::read_to_string(cfg_dir.as_ref().unwrap().path().join("config.toml"));
+        assert!(result.is_ok());
+        let expected = r#"[fonts]
+size = 42
+
+[navigation]
+mode = "Plain"
+
+[bindings]
+keys = [{ key = "z", with = "control", action = "None" }, { key = "=", with = "control", action = "IncreaseFontSize" }, { key = "+", with = "control", action = "IncreaseFontSize" }, { key = "-", with = "control", action = "DecreaseFontSize" }, { key = "0", with = "control", action = "ResetFontSize" }]
+"#;
+        assert_eq!(result.unwrap_or_default(), expected)
+    }
+
+    #[test]
+    fn test_rio_keys_of_base_config() {
+        let mut term_conf: DocumentMut = r#"[bindings]
+keys = [
+  { key = "z", with = "control", action = "Quit" },
+  { key = "v", with = "shift | control", action = "Paste" },
+  { key = "n", with = "control", action = "CreateWindow" },
+]
+"#
+        .parse()
+        .unwrap();
+        let conf = config::Config {
+            backend: Some(config::Backend::Rio),
+            keys: vec![config::KeyBinding {
+                key: "ctrl+shift+v".parse().unwrap(),
+                action: config::KeyAction::Passthrough,
+            }],
+            ..Default::default()
+        };
+        Rio::set_key_bindings(&mut term_conf, &conf);
+        let keys = term_conf["bindings"]["keys"].as_array().unwrap();
+        let actions: Vec<(&str, &str)> = keys
+            .iter()
+            .map(|k| {
+                let k = k.as_inline_table().unwrap();
+                (
+                    k.get("key").unwrap().as_str().unwrap(),
+                    k.get("action").unwrap().as_str().unwrap(),
+                )
+            })
+            .collect();
+        assert_eq!(
+            actions,
+            [
+                ("n", "CreateWindow"),
+                ("z", "None"),
+                ("=", "IncreaseFontSize"),
+                ("+", "IncreaseFontSize"),
+                ("-", "DecreaseFontSize"),
+                ("0", "ResetFontSize"),
+                ("v", "ReceiveChar"),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_rio_copy_themes() {
+        let themes = tempfile::tempdir().unwrap();
+        fs::write(themes.path().join("dracula.toml"), "[colors]\n").unwrap();
+        let cfg_dir = tempfile::tempdir().unwrap();
+        Rio::copy_themes(
+            cfg_dir.path(),
+            &[
+                String::new(),
+                "/nonexistent/rio/themes".to_string(),
+                themes.path().to_str().unwrap().to_string(),
+            ],
+        );
+        let result = fs::read_to_string(cfg_dir.path().join("themes").join("dracula.toml"));
+        assert_eq!(result.unwrap_or_default(), "[colors]\n");
+    }
+}
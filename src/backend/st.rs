@@ -0,0 +1,94 @@
+use super::Functions;
+use crate::config::Config;
+use crate::error::GlrnvimError;
+use std::path::PathBuf;
+
+pub const ST_NAME: &str = "st";
+
+struct St {
+    exe_path: PathBuf,
+    pub args: Vec<String>,
+}
+
+pub fn init(config: &Config) -> Result<Box<dyn Functions>, GlrnvimError> {
+    let exe_path = super::exe_path(&config.term_exe_path, ST_NAME)?;
+
+    Ok(Box::new(St {
+        exe_path,
+        args: vec![],
+    }))
+}
+
+impl St {
+    fn init_args(&mut self, config: &Config) {
+        // st takes only one font pattern. Fallback fonts are picked by fontconfig.
+        let mut fn_arg = match config.fonts.first() {
+            Some(font) => font.to_string(),
+            None => String::new(),
+        };
+        if config.font_size > 0.0 {
+            fn_arg.push_str(format!(":size={}", config.font_size).as_str());
+        }
+        if !fn_arg.is_empty() {
+            self.args.push(String::from("-f"));
+            self.args.push(fn_arg);
+        }
+
+        if let Some(geometry) = super::x11_geometry(&config.window) {
+            self.args.push(String::from("-g"));
+            self.args.push(geometry);
+        }
+    }
+}
+
+impl Functions for St {
+    fn create_command(&mut self, config: &Config) -> std::process::Command {
+        self.init_args(config);
+        let mut command = std::process::Command::new(&self.exe_path);
+
+        command.arg("-c");
+        command.arg("glrnvim");
+        command.args(&self.args);
+        command.arg("-e");
+        command.arg(&config.nvim_exe_path);
+        command.args(super::common_args(config));
+        command
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config;
+
+    #[test]
+    fn test_st_font_args() {
+        let conf = config::Config {
+            backend: Some(config::Backend::St),
+            font_size: 14.0,
+            fonts: vec!["test_font".into(), "fallback_font".into()],
+            ..Default::default()
+        };
+        let mut st = St {
+            exe_path: PathBuf::new(),
+            args: vec![],
+        };
+        st.init_args(&conf);
+        assert_eq!(st.args, vec!["-f", "test_font:size=14"]);
+    }
+
+    #[test]
+    fn test_st_font_size_only() {
+        let conf = config::Config {
+            backend: Some(config::Backend::St),
+            font_size: 14.0,
+            ..Default::default()
+        };
+        let mut st = St {
+            exe_path: PathBuf::new(),
+            args: vec![],
+        };
+        st.init_args(&conf);
+        assert_eq!(st.args, vec!["-f", ":size=14"]);
+    }
+}
@@ -1,5 +1,5 @@
 use super::Functions;
-use crate::config::Config;
+use crate::config::{Config, CursorShape, KeyAction};
 use crate::error::GlrnvimError;
 use std::path::PathBuf;
 
@@ -21,19 +21,122 @@ pub fn init(config: &Config) -> Result<Box<dyn Functions>, GlrnvimError> {
 
 impl Urxvt {
     fn init_args(&mut self, config: &Config) {
-        let mut fn_arg = String::from("");
-        for font in &config.fonts {
-            if fn_arg.is_empty() {
-                self.args.push(String::from("-fn"));
-                fn_arg.push_str(
-                    format!("xft:{}:size={}:antialias=true", font, config.font_size).as_str(),
-                );
-            } else {
-                fn_arg.push_str(format!(",xft:{}:antialias=true", font).as_str());
+        // Each font takes its own size and style. The major one uses font.normal.style.
+        let mut fonts: Vec<String> = Vec::new();
+        for (i, font) in config.fonts.iter().enumerate() {
+            let mut xft = format!("xft:{}", font.family);
+            let style = match i {
+                0 => config.font.normal_style(),
+                _ => font.style.as_deref(),
+            };
+            if let Some(style) = style {
+                xft.push_str(format!(":style={}", style).as_str());
             }
+            let size = font.size_or(config.font_size);
+            if size > 0.0 {
+                xft.push_str(format!(":size={}", size).as_str());
+            }
+            xft.push_str(":antialias=true");
+            fonts.push(xft);
+        }
+        if !fonts.is_empty() {
+            self.args.push(String::from("-fn"));
+            self.args.push(fonts.join(","));
         }
-        if !fn_arg.is_empty() {
-            self.args.push(fn_arg);
+
+        if let Some(line_height) = config.line_height {
+            self.args.push("-lsp".to_string());
+            self.args.push(line_height.to_string());
+        }
+        if let Some(letter_spacing) = config.letter_spacing {
+            self.args.push("-letsp".to_string());
+            self.args.push(letter_spacing.to_string());
+        }
+
+        let colors = &config.colors;
+        for (arg, color) in [
+            ("-fg", &colors.foreground),
+            ("-bg", &colors.background),
+            ("-cr", &colors.cursor),
+            ("-hfc", &colors.selection_foreground),
+            ("-hc", &colors.selection_background),
+        ] {
+            if let Some(c) = color {
+                self.args.push(arg.to_string());
+                self.args.push(c.to_string());
+            }
+        }
+        for (i, c) in colors.palette.iter().enumerate() {
+            self.args.push(format!("-color{}", i));
+            self.args.push(c.to_string());
+        }
+
+        // urxvt cannot start maximized or in fullscreen.
+        if let Some(geometry) = super::x11_geometry(&config.window) {
+            self.args.push("-geometry".to_string());
+            self.args.push(geometry);
+        }
+        // The opacity is not supported since it requires a compositor and the rgba colors.
+        if let Some(decorations) = config.window.decorations {
+            self.args
+                .push(if decorations { "+bl" } else { "-bl" }.to_string());
+        }
+        if let Some(padding) = config.window.padding {
+            // Only one internal border width for both directions.
+            self.args.push("-b".to_string());
+            self.args.push(padding.x.to_string());
+        }
+
+        // urxvt has no built-in shortcuts except the ISO 14755 mode started by ctrl+shift.
+        if config.passthrough_all_keys {
+            self.args.push("+iso14755".to_string());
+            self.args.push("+iso14755_52".to_string());
+        }
+        for binding in config.key_bindings() {
+            let key = &binding.key;
+            let passed = matches!(binding.action, KeyAction::Passthrough | KeyAction::CsiU);
+            if passed && key.ctrl && key.shift && !self.args.iter().any(|a| a == "+iso14755") {
+                self.args.push("+iso14755".to_string());
+                self.args.push("+iso14755_52".to_string());
+            }
+            let action = match binding.action {
+                KeyAction::Unbind => "builtin-string:",
+                KeyAction::Passthrough => continue,
+                KeyAction::CsiU => &format!("string:\\033[{}", super::csi_u(key)),
+                KeyAction::Copy => "eval:selection_to_clipboard",
+                KeyAction::Paste => "eval:paste_clipboard",
+                _ => {
+                    // The default font zoom keys are skipped silently.
+                    if config.keys.contains(&binding) {
+                        log::warn!("Key action {:?} is not supported by urxvt.", binding.action);
+                    }
+                    continue;
+                }
+            };
+            if key.super_key {
+                log::warn!("The super modifier of {} is not supported by urxvt.", key);
+                continue;
+            }
+            let mut keysym = super::x11_keysym(&key.key);
+            // The keysym of a shifted letter is in uppercase.
+            if key.shift && keysym.len() == 1 {
+                keysym = keysym.to_uppercase();
+            }
+            let mut mods = super::key_mods(key, ["C-", "S-", "M-", ""]).join("");
+            mods.push_str(&keysym);
+            self.args.push(format!("-keysym.{}:", mods));
+            self.args.push(action.to_string());
+        }
+
+        // urxvt doesn't support the beam shape and the blink interval.
+        match config.cursor.shape {
+            Some(CursorShape::Underline) => self.args.push("-uc".to_string()),
+            Some(CursorShape::Block) => self.args.push("+uc".to_string()),
+            _ => {}
+        }
+        if let Some(blink) = config.cursor.blink {
+            self.args
+                .push(if blink { "-bc" } else { "+bc" }.to_string());
         }
     }
 }
@@ -45,13 +148,136 @@ impl Functions for Urxvt {
 
         command.arg("-name");
         command.arg("glrnvim");
-        // Disable Ctrl-Z. Shouldn't this pass ^Z to nvim??
-        command.arg("-keysym.C-z:");
-        command.arg("builtin-string:");
         command.args(&self.args);
         command.arg("-e");
         command.arg(&config.nvim_exe_path);
-        command.args(super::COMMON_ARGS);
+        command.args(super::common_args(config));
         command
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config;
+
+    #[test]
+    fn test_urxvt_colors() {
+        let conf = config::Config {
+            backend: Some(config::Backend::Urxvt),
+            colors: config::Colors {
+                foreground: Some("#c5c8c6".parse().unwrap()),
+                selection_background: Some("#373b41".parse().unwrap()),
+                palette: (0..16)
+                    .map(|i| config::Color { r: i, g: i, b: i })
+                    .collect(),
+                ..Default::default()
+            },
+            ..Default::default()
+        };
+        let mut urxvt = Urxvt {
+            exe_path: PathBuf::new(),
+            args: vec![],
+        };
+        urxvt.init_args(&conf);
+        assert_eq!(
+            urxvt.args[..8],
+            [
+                "-fg", "#c5c8c6", "-hc", "#373b41", "-color0", "#000000", "-color1", "#010101"
+            ]
+        );
+        assert_eq!(urxvt.args[34..36], ["-color15", "#0f0f0f"]);
+    }
+
+    #[test]
+    fn test_urxvt_window_geometry() {
+        let conf = config::Config {
+            backend: Some(config::Backend::Urxvt),
+            window: config::Window {
+                columns: Some(100),
+                lines: Some(40),
+                position: Some(config::Position { x: 10, y: -5 }),
+                ..Default::default()
+            },
+            ..Default::default()
+        };
+        let mut urxvt = Urxvt {
+            exe_path: PathBuf::new(),
+            args: vec![],
+        };
+        urxvt.init_args(&conf);
+        assert_eq!(urxvt.args[..2], ["-geometry", "100x40+10-5"]);
+    }
+
+    #[test]
+    fn test_urxvt_window_style() {
+        let conf = config::Config {
+            backend: Some(config::Backend::Urxvt),
+            window: config::Window {
+                decorations: Some(false),
+                padding: Some(config::Padding { x: 5, y: 2 }),
+                opacity: Some(0.9),
+                ..Default::default()
+            },
+            ..Default::default()
+        };
+        let mut urxvt = Urxvt {
+            exe_path: PathBuf::new(),
+            args: vec![],
+        };
+        urxvt.init_args(&conf);
+        // The opacity is skipped, and only the horizontal padding is used.
+        assert_eq!(urxvt.args[..3], ["-bl", "-b", "5"]);
+    }
+
+    #[test]
+    fn test_urxvt_fractional_font_size() {
+        let conf = config::Config {
+            backend: Some(config::Backend::Urxvt),
+            font_size: 11.5,
+            fonts: vec!["test_font".into()],
+            ..Default::default()
+        };
+        let mut urxvt = Urxvt {
+            exe_path: PathBuf::new(),
+            args: vec![],
+        };
+        urxvt.init_args(&conf);
+        assert_eq!(
+            urxvt.args[..2],
+            ["-fn", "xft:test_font:size=11.5:antialias=true"]
+        );
+    }
+
+    #[test]
+    fn test_urxvt_font_args() {
+        let conf = config::Config {
+            backend: Some(config::Backend::Urxvt),
+            font_size: 14.0,
+            fonts: vec![
+                "test_font".into(),
+                config::FontSpec {
+                    size: Some(10.0),
+                    style: Some("Bold".to_string()),
+                    ..config::FontSpec::from("sized_font")
+                },
+                "fallback_font".into(),
+            ],
+            ..Default::default()
+        };
+        let mut urxvt = Urxvt {
+            exe_path: PathBuf::new(),
+            args: vec![],
+        };
+        urxvt.init_args(&conf);
+        assert_eq!(
+            urxvt.args[..2],
+            [
+                "-fn",
+                "xft:test_font:size=14:antialias=true,\
+                 xft:sized_font:style=Bold:size=10:antialias=true,\
+                 xft:fallback_font:size=14:antialias=true",
+            ]
+        );
+    }
+}
@@ -0,0 +1,400 @@
+use super::Functions;
+use crate::config::{Color, Config, CursorShape};
+use crate::error::GlrnvimError;
+use std::path::PathBuf;
+use std::process::Command;
+extern crate log;
+
+pub const GNOME_TERMINAL_NAME: &str = "gnome-terminal";
+pub const KGX_NAME: &str = "kgx";
+pub const PTYXIS_NAME: &str = "ptyxis";
+
+const DCONF_NAME: &str = "dconf";
+const PROFILES_PATH: &str = "/org/gnome/terminal/legacy/profiles:";
+// The profile dedicated to glrnvim. It is created or updated when glrnvim starts with
+// gnome-terminal if gnome_terminal_profile is set, and won't touch any other profiles.
+const GLRNVIM_PROFILE_UUID: &str = "6c8e1c04-5d4f-4bd4-9a0b-676c726e7669";
+// The built-in default profile of gnome-terminal. It is not in the profile list until the user
+// changes the profile settings.
+const DEFAULT_PROFILE_UUID: &str = "b1dcc9dd-5262-4d8d-a863-c897e6d979b9";
+
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+enum Flavor {
+    GnomeTerminal,
+    Kgx,
+    Ptyxis,
+}
+
+struct Vte {
+    flavor: Flavor,
+    exe_path: PathBuf,
+    profile_written: bool,
+}
+
+pub fn init_gnome_terminal(config: &Config) -> Result<Box<dyn Functions>, GlrnvimError> {
+    init(config, Flavor::GnomeTerminal, GNOME_TERMINAL_NAME)
+}
+
+pub fn init_kgx(config: &Config) -> Result<Box<dyn Functions>, GlrnvimError> {
+    init(config, Flavor::Kgx, KGX_NAME)
+}
+
+pub fn init_ptyxis(config: &Config) -> Result<Box<dyn Functions>, GlrnvimError> {
+    init(config, Flavor::Ptyxis, PTYXIS_NAME)
+}
+
+fn init(
+    config: &Config,
+    flavor: Flavor,
+    exe_name: &str,
+) -> Result<Box<dyn Functions>, GlrnvimError> {
+    let exe_path = super::exe_path(&config.term_exe_path, exe_name)?;
+
+    Ok(Box::new(Vte {
+        flavor,
+        exe_path,
+        profile_written: false,
+    }))
+}
+
+// Quote a string as a GVariant string literal.
+fn gvariant_str(s: &str) -> String {
+    format!("'{}'", s.replace('\\', "\\\\").replace('\'', "\\'"))
+}
+
+impl Vte {
+    // The profile is kept in the preferences of gnome-terminal, so it is only written when the user
+    // opts in and there is something to apply.
+    fn should_write_profile(&self, config: &Config) -> bool {
+        let has_style = !config.fonts.is_empty()
+            || config.font_size > 0.0
+            || !config.colors.is_empty()
+            || config.cursor.has_style();
+        if self.flavor != Flavor::GnomeTerminal || config.term_config_path.is_some() || !has_style {
+            return false;
+        }
+        if !config.gnome_terminal_profile {
+            if !config.fonts.is_empty() || !config.colors.is_empty() || config.cursor.has_style() {
+                log::warn!(
+                    "Fonts, colors and cursor require gnome_terminal_profile for gnome-terminal."
+                );
+            }
+            return false;
+        }
+        true
+    }
+
+    // The dconf keys and values of the glrnvim profile. The keys with None values should be reset
+    // to their defaults, since the profile may have been written by a different glrnvim config.
+    fn profile_settings(config: &Config) -> Vec<(String, Option<String>)> {
+        let mut settings: Vec<(&str, Option<String>)> = vec![
+            ("visible-name", Some(gvariant_str("glrnvim"))),
+            ("scrollbar-policy", Some(gvariant_str("never"))),
+        ];
+
+        if !config.fonts.is_empty() || config.font_size > 0.0 {
+            // VTE doesn't support fallback fonts. Just use the first chosen font.
+            let mut font = match config.fonts.first() {
+                Some(f) => f.to_string(),
+                None => "Monospace".to_string(),
+            };
+            if config.font_size > 0.0 {
+                font.push_str(format!(" {}", config.font_size).as_str());
+            }
+            settings.push(("font", Some(gvariant_str(&font))));
+            settings.push(("use-system-font", Some("false".to_string())));
+        } else {
+            settings.push(("font", None));
+            settings.push(("use-system-font", None));
+        }
+
+        let colors = &config.colors;
+        let color = |c: &Option<Color>| c.map(|c| gvariant_str(&c.to_string()));
+        let is_set = |set: bool| if set { Some("true".to_string()) } else { None };
+        let theme_colors = colors.foreground.is_none() && colors.background.is_none();
+        settings.push((
+            "use-theme-colors",
+            if theme_colors {
+                None
+            } else {
+                Some("false".to_string())
+            },
+        ));
+        settings.push(("foreground-color", color(&colors.foreground)));
+        settings.push(("background-color", color(&colors.background)));
+        // The text color under the cursor is the background color.
+        settings.push(("cursor-colors-set", is_set(colors.cursor.is_some())));
+        settings.push(("cursor-background-color", color(&colors.cursor)));
+        settings.push((
+            "cursor-foreground-color",
+            colors.cursor.and(color(&colors.background)),
+        ));
+        let highlight =
+            colors.selection_foreground.is_some() || colors.selection_background.is_some();
+        settings.push(("highlight-colors-set", is_set(highlight)));
+        settings.push((
+            "highlight-foreground-color",
+            color(&colors.selection_foreground),
+        ));
+        settings.push((
+            "highlight-background-color",
+            color(&colors.selection_background),
+        ));
+        let palette = if colors.palette.is_empty() {
+            None
+        } else {
+            let palette: Vec<String> = colors
+                .palette
+                .iter()
+                .map(|c| gvariant_str(&c.to_string()))
+                .collect();
+            Some(format!("[{}]", palette.join(", ")))
+        };
+        settings.push(("palette", palette));
+
+        // The blink interval is a global setting of gnome-terminal.
+        let cursor = &config.cursor;
+        let shape = cursor.shape.map(|s| match s {
+            CursorShape::Block => gvariant_str("block"),
+            CursorShape::Beam => gvariant_str("ibeam"),
+            CursorShape::Underline => gvariant_str("underline"),
+        });
+        settings.push(("cursor-shape", shape));
+        let blink = cursor
+            .blink
+            .map(|b| gvariant_str(if b { "on" } else { "off" }));
+        settings.push(("cursor-blink-mode", blink));
+
+        let profile_path = format!("{}/:{}", PROFILES_PATH, GLRNVIM_PROFILE_UUID);
+        settings
+            .into_iter()
+            .map(|(key, value)| (format!("{}/{}", profile_path, key), value))
+            .collect()
+    }
+
+    // Add the glrnvim profile to the given profile list, which is a GVariant string array.
+    // None is returned if the profile is in the list already.
+    fn updated_profile_list(list: &str) -> Option<String> {
+        let mut uuids: Vec<String> = list
+            .trim()
+            .trim_start_matches('[')
+            .trim_end_matches(']')
+            .split(',')
+            .map(|s| s.trim().trim_matches('\'').to_string())
+            .filter(|s| !s.is_empty())
+            .collect();
+        if uuids.iter().any(|s| s == GLRNVIM_PROFILE_UUID) {
+            return None;
+        }
+        if uuids.is_empty() {
+            uuids.push(DEFAULT_PROFILE_UUID.to_string());
+        }
+        uuids.push(GLRNVIM_PROFILE_UUID.to_string());
+
+        let uuids: Vec<String> = uuids.iter().map(|s| gvariant_str(s)).collect();
+        Some(format!("[{}]", uuids.join(", ")))
+    }
+
+    // Write the value to the key, or reset the key if the value is None.
+    fn dconf_write(dconf: &PathBuf, key: &str, value: Option<&str>) -> Result<(), GlrnvimError> {
+        let mut command = Command::new(dconf);
+        match value {
+            Some(value) => command.arg("write").arg(key).arg(value),
+            None => command.arg("reset").arg(key),
+        };
+        let status = command
+            .status()
+            .map_err(|e| GlrnvimError::new(format!("Failed to run dconf. {}", e)))?;
+        if !status.success() {
+            return Err(GlrnvimError::new(format!(
+                "Failed to write dconf key '{}'.",
+                key
+            )));
+        }
+        Ok(())
+    }
+
+    // Create or update the dedicated glrnvim profile of gnome-terminal.
+    fn write_profile(config: &Config) -> Result<(), GlrnvimError> {
+        let dconf = super::find_executable(DCONF_NAME)?;
+
+        let list_key = format!("{}/list", PROFILES_PATH);
+        let output = Command::new(&dconf)
+            .arg("read")
+            .arg(&list_key)
+            .output()
+            .map_err(|e| GlrnvimError::new(format!("Failed to run dconf. {}", e)))?;
+        let list = String::from_utf8_lossy(&output.stdout);
+        if let Some(list) = Vte::updated_profile_list(&list) {
+            Vte::dconf_write(&dconf, &list_key, Some(&list))?;
+        }
+
+        for (key, value) in Vte::profile_settings(config) {
+            Vte::dconf_write(&dconf, &key, value.as_deref())?;
+        }
+        Ok(())
+    }
+}
+
+impl Functions for Vte {
+    fn pre_start(&mut self, config: &Config) {
+        if !self.should_write_profile(config) {
+            return;
+        }
+        match Vte::write_profile(config) {
+            Ok(()) => self.profile_written = true,
+            Err(e) => log::warn!("Cannot create the glrnvim profile. {}", e),
+        }
+    }
+
+    fn create_command(&mut self, config: &Config) -> std::process::Command {
+        let mut command = std::process::Command::new(&self.exe_path);
+
+        match self.flavor {
+            Flavor::GnomeTerminal => {
+                command.arg("--wait");
+                command.arg("--class=glrnvim");
+                if let Some(profile) = config.term_config_path.as_ref() {
+                    // Use the given profile as it is.
+                    command.arg(format!("--profile={}", profile));
+                } else if self.profile_written {
+                    command.arg(format!("--profile={}", GLRNVIM_PROFILE_UUID));
+                }
+                if let Some(geometry) = super::x11_geometry(&config.window) {
+                    command.arg(format!("--geometry={}", geometry));
+                }
+                if config.window.fullscreen {
+                    command.arg("--full-screen");
+                } else if config.window.maximized {
+                    command.arg("--maximize");
+                }
+            }
+            Flavor::Kgx | Flavor::Ptyxis => {
+                if self.flavor == Flavor::Ptyxis {
+                    command.arg("--new-window");
+                }
+                if !config.fonts.is_empty()
+                    || !config.colors.is_empty()
+                    || config.term_config_path.is_some()
+                {
+                    log::warn!(
+                        "Fonts, colors and term_config_path are not supported by this backend."
+                    );
+                }
+            }
+        }
+
+        if let Ok(current_dir) = std::env::current_dir() {
+            command.arg(format!("--working-directory={}", current_dir.display()));
+        }
+
+        command.arg("--");
+        command.arg(&config.nvim_exe_path);
+        command.args(super::common_args(config));
+
+        command
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config;
+
+    #[test]
+    fn test_profile_settings() {
+        let conf = config::Config {
+            backend: Some(config::Backend::GnomeTerminal),
+            font_size: 14.0,
+            fonts: vec!["test_font".into(), "fallback_font".into()],
+            colors: config::Colors {
+                background: Some("#1d1f21".parse().unwrap()),
+                cursor: Some("#aabbcc".parse().unwrap()),
+                ..Default::default()
+            },
+            ..Default::default()
+        };
+        let settings = Vte::profile_settings(&conf);
+        let profile_path = format!("{}/:{}", PROFILES_PATH, GLRNVIM_PROFILE_UUID);
+        let expected: Vec<(String, Option<String>)> = [
+            ("visible-name", Some("'glrnvim'")),
+            ("scrollbar-policy", Some("'never'")),
+            ("font", Some("'test_font 14'")),
+            ("use-system-font", Some("false")),
+            ("use-theme-colors", Some("false")),
+            ("foreground-color", None),
+            ("background-color", Some("'#1d1f21'")),
+            ("cursor-colors-set", Some("true")),
+            ("cursor-background-color", Some("'#aabbcc'")),
+            ("cursor-foreground-color", Some("'#1d1f21'")),
+            ("highlight-colors-set", None),
+            ("highlight-foreground-color", None),
+            ("highlight-background-color", None),
+            ("palette", None),
+            ("cursor-shape", None),
+            ("cursor-blink-mode", None),
+        ]
+        .iter()
+        .map(|(k, v)| (format!("{}/{}", profile_path, k), v.map(|v| v.to_string())))
+        .collect();
+        assert_eq!(settings, expected);
+    }
+
+    #[test]
+    fn test_updated_profile_list() {
+        assert_eq!(
+            Vte::updated_profile_list(""),
+            Some(format!(
+                "['{}', '{}']",
+                DEFAULT_PROFILE_UUID, GLRNVIM_PROFILE_UUID
+            ))
+        );
+        assert_eq!(
+            Vte::updated_profile_list("['abc', 'def']\n"),
+            Some(format!("['abc', 'def', '{}']", GLRNVIM_PROFILE_UUID))
+        );
+        assert_eq!(
+            Vte::updated_profile_list(&format!("['abc', '{}']", GLRNVIM_PROFILE_UUID)),
+            None
+        );
+    }
+
+    #[test]
+    fn test_should_write_profile() {
+        let vte = Vte {
+            flavor: Flavor::GnomeTerminal,
+            exe_path: PathBuf::new(),
+            profile_written: false,
+        };
+        let conf = config::Config {
+            backend: Some(config::Backend::GnomeTerminal),
+            fonts: vec!["test_font".into()],
+            font_size: 12.0,
+            ..Default::default()
+        };
+        assert!(!vte.should_write_profile(&conf));
+        let conf = config::Config {
+            gnome_terminal_profile: true,
+            ..conf
+        };
+        assert!(vte.should_write_profile(&conf));
+        // The font size set by main.rs is applied as in the other backends.
+        let conf = config::Config {
+            fonts: Vec::new(),
+            ..conf
+        };
+        assert!(vte.should_write_profile(&conf));
+        // Nothing to apply when the terminal's config is loaded.
+        let conf = config::Config {
+            font_size: 0.0,
+            load_term_conf: true,
+            ..conf
+        };
+        assert!(!vte.should_write_profile(&conf));
+    }
+
+    #[test]
+    fn test_gvariant_str() {
+        assert_eq!(gvariant_str("It's"), "'It\\'s'");
+    }
+}
@@ -1,5 +1,5 @@
 use super::Functions;
-use crate::config::Config;
+use crate::config::{Config, CursorShape, FontSpec, KeyAction, KeyBinding};
 use crate::error::GlrnvimError;
 use std::io::Write;
 use std::path::PathBuf;
@@ -23,26 +23,315 @@ pub fn init(config: &Config) -> Result<Box<dyn Functions>, GlrnvimError> {
 
 impl Wezterm {
     fn init_args(&mut self, config: &Config) {
-        let mut fn_arg = String::from("");
-        if !&config.fonts.is_empty() {
+        let font = &config.font;
+        if let Some((first, rest)) = config.fonts.split_first() {
+            let attrs = font.normal_style().map(Wezterm::style_attrs);
             self.args.push("--config".to_string());
-            fn_arg = String::from("font = require('wezterm').font_with_fallback({");
-            for font in &config.fonts {
-                fn_arg = format!("{} \"{}\",", fn_arg, font);
+            self.args.push(format!(
+                "font = {}",
+                Wezterm::font_with_fallback(
+                    &first.family,
+                    attrs.as_deref(),
+                    rest,
+                    config.font_size
+                )
+            ));
+
+            let mut rules: Vec<String> = Vec::new();
+            for (key, face) in font.faces() {
+                let Some(face) = face else { continue };
+                let (intensity, italic, default_attrs) = match key {
+                    "bold" => ("Bold", false, "weight = \"Bold\""),
+                    "italic" => ("Normal", true, "style = \"Italic\""),
+                    _ => ("Bold", true, "weight = \"Bold\", style = \"Italic\""),
+                };
+                let attrs = match face.style.as_deref() {
+                    Some(style) => Wezterm::style_attrs(style),
+                    None => default_attrs.to_string(),
+                };
+                let family = face.family.as_deref().unwrap_or(&first.family);
+                rules.push(format!(
+                    "{{ intensity = \"{}\", italic = {}, font = {} }}",
+                    intensity,
+                    italic,
+                    Wezterm::font_with_fallback(family, Some(&attrs), rest, config.font_size)
+                ));
+            }
+            if !rules.is_empty() {
+                self.args.push("--config".to_string());
+                self.args
+                    .push(format!("font_rules = {{ {} }}", rules.join(", ")));
             }
-            fn_arg.push_str("})");
         }
-        if !fn_arg.is_empty() {
-            self.args.push(fn_arg);
+        if !font.features.is_empty() {
+            let features: Vec<String> =
+                font.features.iter().map(|f| format!("\"{}\"", f)).collect();
+            self.args.push("--config".to_string());
+            self.args
+                .push(format!("harfbuzz_features = {{ {} }}", features.join(", ")));
         }
-        if config.font_size != 0 {
+        if config.font_size > 0.0 {
             self.args.push("--config".to_string());
             self.args.push(format!("font_size={}", config.font_size));
         }
+        // Wezterm only takes the spacing as multipliers of the cell size, which depends on the font.
+        if config.line_height.is_some() {
+            log::warn!("line_height is not supported by wezterm.");
+        }
+        if config.letter_spacing.is_some() {
+            log::warn!("letter_spacing is not supported by wezterm.");
+        }
+        if let Some((columns, lines)) = config.window.size() {
+            self.args.push("--config".to_string());
+            self.args.push(format!("initial_cols={}", columns));
+            self.args.push("--config".to_string());
+            self.args.push(format!("initial_rows={}", lines));
+        }
+        if let Some(decorations) = config.window.decorations {
+            let decorations = if decorations {
+                "TITLE | RESIZE"
+            } else {
+                "NONE"
+            };
+            self.args.push("--config".to_string());
+            self.args
+                .push(format!("window_decorations = \"{}\"", decorations));
+        }
+        if let Some(padding) = config.window.padding {
+            self.args.push("--config".to_string());
+            self.args.push(format!(
+                "window_padding = {{ left = {x}, right = {x}, top = {y}, bottom = {y} }}",
+                x = padding.x,
+                y = padding.y
+            ));
+        }
+        if let Some(opacity) = config.window.opacity {
+            self.args.push("--config".to_string());
+            self.args
+                .push(format!("window_background_opacity = {}", opacity));
+        }
+        let cursor = &config.cursor;
+        if cursor.shape.is_some() || cursor.blink.is_some() {
+            let blink = if cursor.blink.unwrap_or(false) {
+                "Blinking"
+            } else {
+                "Steady"
+            };
+            let shape = match cursor.shape.unwrap_or(CursorShape::Block) {
+                CursorShape::Block => "Block",
+                CursorShape::Beam => "Bar",
+                CursorShape::Underline => "Underline",
+            };
+            self.args.push("--config".to_string());
+            self.args
+                .push(format!("default_cursor_style = \"{}{}\"", blink, shape));
+        }
+        if let Some(interval) = cursor.blink_interval {
+            self.args.push("--config".to_string());
+            self.args.push(format!("cursor_blink_rate = {}", interval));
+        }
+        if let Some(colors_arg) = Wezterm::colors_arg(config) {
+            self.args.push("--config".to_string());
+            self.args.push(colors_arg);
+        }
+        // The keys table replaces the one in wezterm's config. Keep the user's table if it is loaded
+        // and glrnvim has no keys of its own to set.
+        let has_keys = !config.keys.is_empty() || config.clipboard.keys().next().is_some();
+        if !config.load_term_conf || config.passthrough_all_keys || has_keys {
+            let keys: Vec<String> = config
+                .key_bindings()
+                .iter()
+                .map(Wezterm::key_binding)
+                .collect();
+            self.args.push("--config".to_string());
+            self.args.push(format!("keys = {{ {} }}", keys.join(", ")));
+        }
+        if config.passthrough_all_keys {
+            self.args.push("--config".to_string());
+            self.args
+                .push("disable_default_key_bindings = true".to_string());
+        }
         self.args.push("--config".to_string());
         self.args.push("enable_tab_bar = false".to_string());
     }
 
+    fn key_binding(binding: &KeyBinding) -> String {
+        let key = match binding.key.key.as_str() {
+            "tab" => "Tab",
+            "enter" => "Enter",
+            "escape" => "Escape",
+            "space" => "Space",
+            "backspace" => "Backspace",
+            "delete" => "Delete",
+            "insert" => "Insert",
+            "home" => "Home",
+            "end" => "End",
+            "page_up" => "PageUp",
+            "page_down" => "PageDown",
+            "up" => "UpArrow",
+            "down" => "DownArrow",
+            "left" => "LeftArrow",
+            "right" => "RightArrow",
+            "plus" => "+",
+            "minus" => "-",
+            "equal" => "=",
+            k => k,
+        };
+        let key = if key.len() > 1 && key.starts_with('f') {
+            key.to_uppercase()
+        } else {
+            key.to_string()
+        };
+        let mods = super::key_mods(&binding.key, ["CTRL", "SHIFT", "ALT", "SUPER"]);
+        let mods = if mods.is_empty() {
+            "NONE".to_string()
+        } else {
+            mods.join("|")
+        };
+        let action = match binding.action {
+            // DisableDefaultAssignment passes the key to the program, Nop swallows it.
+            KeyAction::Unbind => "Nop",
+            KeyAction::Passthrough => "DisableDefaultAssignment",
+            KeyAction::CsiU => &format!("SendString '\\x1b[{}'", super::csi_u(&binding.key)),
+            KeyAction::Copy => "CopyTo('Clipboard')",
+            KeyAction::Paste => "PasteFrom('Clipboard')",
+            // The font size actions of wezterm multiply the size by 1.1, so zoom by the step instead.
+            KeyAction::IncreaseFontSize => {
+                return Wezterm::font_size_binding(&key, &mods, Some(super::FONT_SIZE_STEP));
+            }
+            KeyAction::DecreaseFontSize => {
+                return Wezterm::font_size_binding(&key, &mods, Some(-super::FONT_SIZE_STEP));
+            }
+            KeyAction::ResetFontSize => return Wezterm::font_size_binding(&key, &mods, None),
+            KeyAction::ToggleFullscreen => "ToggleFullScreen",
+        };
+        format!(
+            "{{ key = \"{}\", mods = \"{}\", action = require('wezterm').action.{} }}",
+            key, mods, action
+        )
+    }
+
+    // Adds the step to the font size of the window, or resets it without a step.
+    fn font_size_binding(key: &str, mods: &str, step: Option<f64>) -> String {
+        let font_size = match step {
+            Some(step) => format!(
+                "(overrides.font_size or window:effective_config().font_size) + ({:.1})",
+                step
+            ),
+            None => "nil".to_string(),
+        };
+        format!(
+            "{{ key = \"{}\", mods = \"{}\", action = require('wezterm').action_callback(function(window) \
+             local overrides = window:get_config_overrides() or {{}}; overrides.font_size = {}; \
+             window:set_config_overrides(overrides) end) }}",
+            key, mods, font_size
+        )
+    }
+
+    fn font_with_fallback(
+        family: &str,
+        attrs: Option<&str>,
+        fallbacks: &[FontSpec],
+        font_size: f64,
+    ) -> String {
+        let mut fonts = match attrs {
+            Some(attrs) if !attrs.is_empty() => {
+                format!("{{ family = \"{}\", {} }},", family, attrs)
+            }
+            _ => format!("\"{}\",", family),
+        };
+        for font in fallbacks {
+            // The size of a fallback font is a scale of font_size, which is 12 by default.
+            let mut attrs: Vec<String> = Vec::new();
+            if let Some(style) = font.style.as_deref() {
+                attrs.push(Wezterm::style_attrs(style));
+            }
+            if let Some(size) = font.size {
+                let base = if font_size > 0.0 { font_size } else { 12.0 };
+                attrs.push(format!("scale = {:.2}", size / base));
+            }
+            attrs.retain(|a| !a.is_empty());
+            if attrs.is_empty() {
+                fonts = format!("{} \"{}\",", fonts, font.family);
+            } else {
+                fonts = format!(
+                    "{} {{ family = \"{}\", {} }},",
+                    fonts,
+                    font.family,
+                    attrs.join(", ")
+                );
+            }
+        }
+        format!("require('wezterm').font_with_fallback({{ {} }})", fonts)
+    }
+
+    // Wezterm selects the font by the weight and the style attributes instead of the style name.
+    // Translate the style name, e.g. "SemiBold Italic" to weight = "DemiBold", style = "Italic".
+    fn style_attrs(style: &str) -> String {
+        let mut name = style.to_lowercase().replace([' ', '-', '_'], "");
+        let mut attrs: Vec<String> = Vec::new();
+        for (slant, value) in [("italic", "Italic"), ("oblique", "Oblique")] {
+            if name.contains(slant) {
+                name = name.replace(slant, "");
+                attrs.push(format!("style = \"{}\"", value));
+            }
+        }
+        let weight = match name.as_str() {
+            "" => None,
+            "thin" | "hairline" => Some("Thin"),
+            "extralight" | "ultralight" => Some("ExtraLight"),
+            "light" => Some("Light"),
+            "demilight" | "semilight" => Some("DemiLight"),
+            "book" => Some("Book"),
+            "regular" | "normal" => Some("Regular"),
+            "medium" => Some("Medium"),
+            "demibold" | "semibold" => Some("DemiBold"),
+            "bold" => Some("Bold"),
+            "extrabold" | "ultrabold" => Some("ExtraBold"),
+            "black" | "heavy" => Some("Black"),
+            "extrablack" | "ultrablack" => Some("ExtraBlack"),
+            _ => {
+                log::warn!("Unknown font style '{}' for wezterm.", style);
+                None
+            }
+        };
+        if let Some(weight) = weight {
+            attrs.insert(0, format!("weight = \"{}\"", weight));
+        }
+        attrs.join(", ")
+    }
+
+    fn colors_arg(config: &Config) -> Option<String> {
+        let colors = &config.colors;
+        if colors.is_empty() {
+            return None;
+        }
+        let mut fields: Vec<String> = Vec::new();
+        for (key, color) in [
+            ("foreground", &colors.foreground),
+            ("background", &colors.background),
+            ("cursor_bg", &colors.cursor),
+            ("cursor_border", &colors.cursor),
+            ("selection_fg", &colors.selection_foreground),
+            ("selection_bg", &colors.selection_background),
+        ] {
+            if let Some(c) = color {
+                fields.push(format!("{} = \"{}\"", key, c));
+            }
+        }
+        if !colors.palette.is_empty() {
+            let quoted = |c: &[crate::config::Color]| {
+                c.iter()
+                    .map(|c| format!("\"{}\"", c))
+                    .collect::<Vec<_>>()
+                    .join(", ")
+            };
+            fields.push(format!("ansi = {{ {} }}", quoted(&colors.palette[..8])));
+            fields.push(format!("brights = {{ {} }}", quoted(&colors.palette[8..])));
+        }
+        Some(format!("colors = {{ {} }}", fields.join(", ")))
+    }
+
     fn create_conf_file(&mut self) -> NamedTempFile {
         let mut file = tempfile::NamedTempFile::new().unwrap();
         writeln!(file, "return {{}}").unwrap();
@@ -67,11 +356,230 @@ impl Functions for Wezterm {
         command.arg("start");
         command.arg("--class");
         command.arg("glrnvim");
+        if let Some(position) = config.window.position {
+            command.arg("--position");
+            command.arg(format!("{},{}", position.x, position.y));
+        }
         command.arg("--cwd");
         command.arg(".");
         command.arg("--");
         command.arg(&config.nvim_exe_path);
-        command.args(super::COMMON_ARGS);
+        command.args(super::common_args(config));
         command
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config;
+
+    #[test]
+    fn test_wezterm_colors_arg() {
+        let mut conf = config::Config {
+            backend: Some(config::Backend::Wezterm),
+            ..Default::default()
+        };
+        assert_eq!(Wezterm::colors_arg(&conf), None);
+        conf.colors = config::Colors {
+            cursor: Some("#c5c8c6".parse().unwrap()),
+            palette: (0..16)
+                .map(|i| config::Color { r: i, g: i, b: i })
+                .collect(),
+            ..Default::default()
+        };
+        assert_eq!(
+            Wezterm::colors_arg(&conf).unwrap(),
+            "colors = { cursor_bg = \"#c5c8c6\", cursor_border = \"#c5c8c6\", \
+             ansi = { \"#000000\", \"#010101\", \"#020202\", \"#030303\", \
+             \"#040404\", \"#050505\", \"#060606\", \"#070707\" }, \
+             brights = { \"#080808\", \"#090909\", \"#0a0a0a\", \"#0b0b0b\", \
+             \"#0c0c0c\", \"#0d0d0d\", \"#0e0e0e\", \"#0f0f0f\" } }"
+        );
+    }
+
+    #[test]
+    fn test_wezterm_window_geometry() {
+        let conf = config::Config {
+            backend: Some(config::Backend::Wezterm),
+            window: config::Window {
+                columns: Some(100),
+                lines: Some(40),
+                position: Some(config::Position { x: 10, y: -5 }),
+                ..Default::default()
+            },
+            ..Default::default()
+        };
+        let mut wezterm = Wezterm {
+            exe_path: PathBuf::new(),
+            args: vec![],
+        };
+        let command = wezterm.create_command(&conf);
+        let args: Vec<_> = command.get_args().collect();
+        assert_eq!(
+            args[2..6],
+            [
+                "--config",
+                "initial_cols=100",
+                "--config",
+                "initial_rows=40"
+            ]
+        );
+        let position = args.iter().position(|a| *a == "--position").unwrap();
+        assert_eq!(args[position + 1], "10,-5");
+    }
+
+    #[test]
+    fn test_wezterm_window_style() {
+        let conf = config::Config {
+            backend: Some(config::Backend::Wezterm),
+            window: config::Window {
+                decorations: Some(false),
+                padding: Some(config::Padding { x: 5, y: 2 }),
+                opacity: Some(0.9),
+                ..Default::default()
+            },
+            ..Default::default()
+        };
+        let mut wezterm = Wezterm {
+            exe_path: PathBuf::new(),
+            args: vec![],
+        };
+        wezterm.init_args(&conf);
+        assert_eq!(
+            wezterm.args[..6],
+            [
+                "--config",
+                "window_decorations = \"NONE\"",
+                "--config",
+                "window_padding = { left = 5, right = 5, top = 2, bottom = 2 }",
+                "--config",
+                "window_background_opacity = 0.9",
+            ]
+        );
+    }
+
+    #[test]
+    fn test_wezterm_style_attrs() {
+        assert_eq!(Wezterm::style_attrs("Bold"), "weight = \"Bold\"");
+        assert_eq!(
+            Wezterm::style_attrs("Semi Bold Italic"),
+            "weight = \"DemiBold\", style = \"Italic\""
+        );
+        assert_eq!(Wezterm::style_attrs("Oblique"), "style = \"Oblique\"");
+        assert_eq!(Wezterm::style_attrs("Retina"), "");
+    }
+
+    #[test]
+    fn test_wezterm_fallback_font_size() {
+        let fallbacks = vec![
+            config::FontSpec {
+                size: Some(9.0),
+                style: Some("Bold".to_string()),
+                ..config::FontSpec::from("cjk_font")
+            },
+            config::FontSpec::from("fallback_font"),
+        ];
+        assert_eq!(
+            Wezterm::font_with_fallback("test_font", None, &fallbacks, 0.0),
+            "require('wezterm').font_with_fallback({ \"test_font\", { family = \"cjk_font\", weight = \"Bold\", scale = 0.75 }, \"fallback_font\", })"
+        );
+    }
+
+    #[test]
+    fn test_wezterm_font_args() {
+        let conf = config::Config {
+            backend: Some(config::Backend::Wezterm),
+            fonts: vec!["test_font".into(), "fallback_font".into()],
+            font: config::Font {
+                italic: Some(config::FontFace {
+                    family: Some("italic_font".to_string()),
+                    style: None,
+                }),
+                features: vec!["-liga".to_string()],
+                ..Default::default()
+            },
+            ..Default::default()
+        };
+        let mut wezterm = Wezterm {
+            exe_path: PathBuf::new(),
+            args: vec![],
+        };
+        wezterm.init_args(&conf);
+        assert_eq!(
+            wezterm.args[..6],
+            [
+                "--config",
+                "font = require('wezterm').font_with_fallback({ \"test_font\", \"fallback_font\", })",
+                "--config",
+                "font_rules = { { intensity = \"Normal\", italic = true, font = require('wezterm').font_with_fallback({ { family = \"italic_font\", style = \"Italic\" }, \"fallback_font\", }) } }",
+                "--config",
+                "harfbuzz_features = { \"-liga\" }",
+            ]
+        );
+    }
+
+    #[test]
+    fn test_wezterm_keys_with_term_conf() {
+        let has_keys = |args: &[String]| args.iter().any(|a| a.starts_with("keys = "));
+        let conf = config::Config {
+            backend: Some(config::Backend::Wezterm),
+            ..Default::default()
+        };
+        let mut wezterm = Wezterm {
+            exe_path: PathBuf::new(),
+            args: vec![],
+        };
+        wezterm.init_args(&conf);
+        assert!(has_keys(&wezterm.args));
+
+        // The keys table of the user is kept.
+        let conf = config::Config {
+            load_term_conf: true,
+            ..conf
+        };
+        let mut wezterm = Wezterm {
+            exe_path: PathBuf::new(),
+            args: vec![],
+        };
+        wezterm.init_args(&conf);
+        assert!(!has_keys(&wezterm.args));
+
+        let conf = config::Config {
+            keys: vec![config::KeyBinding {
+                key: "ctrl+shift+c".parse().unwrap(),
+                action: config::KeyAction::Passthrough,
+            }],
+            ..conf
+        };
+        let mut wezterm = Wezterm {
+            exe_path: PathBuf::new(),
+            args: vec![],
+        };
+        wezterm.init_args(&conf);
+        assert!(has_keys(&wezterm.args));
+    }
+
+    #[test]
+    fn test_wezterm_font_size_keys() {
+        let binding = |action| config::KeyBinding {
+            key: "ctrl+equal".parse().unwrap(),
+            action,
+        };
+        assert_eq!(
+            Wezterm::key_binding(&binding(config::KeyAction::IncreaseFontSize)),
+            "{ key = \"=\", mods = \"CTRL\", action = require('wezterm').action_callback(function(window) \
+             local overrides = window:get_config_overrides() or {}; \
+             overrides.font_size = (overrides.font_size or window:effective_config().font_size) + (1.0); \
+             window:set_config_overrides(overrides) end) }"
+        );
+        assert!(
+            Wezterm::key_binding(&binding(config::KeyAction::DecreaseFontSize))
+                .contains("overrides.font_size = (overrides.font_size or window:effective_config().font_size) + (-1.0);")
+        );
+        assert!(
+            Wezterm::key_binding(&binding(config::KeyAction::ResetFontSize))
+                .contains("overrides.font_size = nil;")
+        );
+    }
+}
@@ -0,0 +1,274 @@
+use super::Functions;
+use crate::config::{Config, CursorShape, KeyAction};
+use crate::error::GlrnvimError;
+use std::path::PathBuf;
+
+pub const XTERM_NAME: &str = "xterm";
+
+struct Xterm {
+    exe_path: PathBuf,
+    pub args: Vec<String>,
+}
+
+pub fn init(config: &Config) -> Result<Box<dyn Functions>, GlrnvimError> {
+    let exe_path = super::exe_path(&config.term_exe_path, XTERM_NAME)?;
+
+    Ok(Box::new(Xterm {
+        exe_path,
+        args: vec![],
+    }))
+}
+
+impl Xterm {
+    fn init_args(&mut self, config: &Config) {
+        // -fa takes a fontconfig pattern, the families in which are used as fallback by orders.
+        if !config.fonts.is_empty() {
+            self.args.push(String::from("-fa"));
+            self.args.push(config.font_families().join(","));
+        }
+        if config.font_size > 0.0 {
+            self.args.push(String::from("-fs"));
+            self.args.push(config.font_size.to_string());
+        }
+
+        let colors = &config.colors;
+        for (arg, color) in [
+            ("-fg", &colors.foreground),
+            ("-bg", &colors.background),
+            ("-cr", &colors.cursor),
+        ] {
+            if let Some(c) = color {
+                self.args.push(arg.to_string());
+                self.args.push(c.to_string());
+            }
+        }
+        let mut resources: Vec<String> = Vec::new();
+        if let Some(c) = colors.selection_foreground {
+            resources.push(format!("*highlightTextColor: {}", c));
+        }
+        if let Some(c) = colors.selection_background {
+            resources.push(format!("*highlightColor: {}", c));
+        }
+        if !resources.is_empty() {
+            resources.push("*highlightColorMode: true".to_string());
+        }
+        for (i, c) in colors.palette.iter().enumerate() {
+            resources.push(format!("*color{}: {}", i, c));
+        }
+        // larger-vt-font and smaller-vt-font switch to the next size of the font menu, so the 6 sizes
+        // of the menu are set to 3 steps below and above font_size.
+        if config.font_size > 0.0 {
+            for (i, steps) in [-3.0, -2.0, -1.0, 1.0, 2.0, 3.0].iter().enumerate() {
+                let size = (config.font_size + steps * super::FONT_SIZE_STEP).max(1.0);
+                resources.push(format!("*faceSize{}: {}", i + 1, size));
+            }
+        }
+        let cursor = &config.cursor;
+        match cursor.shape {
+            Some(CursorShape::Beam) => resources.push("*cursorBar: true".to_string()),
+            Some(CursorShape::Underline) => resources.push("*cursorUnderLine: true".to_string()),
+            _ => {}
+        }
+        if let Some(interval) = cursor.blink_interval {
+            resources.push(format!("*cursorOnTime: {}", interval));
+            resources.push(format!("*cursorOffTime: {}", interval));
+        }
+        if let Some(blink) = cursor.blink {
+            self.args
+                .push(if blink { "-bc" } else { "+bc" }.to_string());
+        }
+        for resource in resources {
+            self.args.push(String::from("-xrm"));
+            self.args.push(resource);
+        }
+
+        // X uses the first translation which matches the key, and a translation matches the keys
+        // with more modifiers as well, e.g. Ctrl<Key>z matches ctrl+shift+z. So the keys of the user
+        // come before the default ones, and the passed through keys insert the key as usual.
+        let (defaults, bindings): (Vec<_>, Vec<_>) = config
+            .key_bindings()
+            .into_iter()
+            .partition(|b| b.action != KeyAction::CsiU && !config.keys.contains(b));
+        let mut translations: Vec<String> = Vec::new();
+        for binding in bindings.into_iter().chain(defaults) {
+            let action = match binding.action {
+                KeyAction::Unbind => "string(\"\")",
+                KeyAction::Passthrough => "insert()",
+                KeyAction::CsiU => {
+                    &format!("string(0x1b) string(\"[{}\")", super::csi_u(&binding.key))
+                }
+                KeyAction::Copy => "copy-selection(CLIPBOARD)",
+                KeyAction::Paste => "insert-selection(CLIPBOARD)",
+                KeyAction::IncreaseFontSize => "larger-vt-font()",
+                KeyAction::DecreaseFontSize => "smaller-vt-font()",
+                KeyAction::ResetFontSize => "set-vt-font(d)",
+                KeyAction::ToggleFullscreen => "fullscreen(toggle)",
+            };
+            let key = &binding.key;
+            let mods = super::key_mods(key, ["Ctrl", "Shift", "Meta", "Mod4"]);
+            translations.push(format!(
+                "{}<Key>{}: {}",
+                mods.join(" "),
+                super::x11_keysym(&key.key),
+                action
+            ));
+        }
+        self.args.push(String::from("-xrm"));
+        self.args.push(format!(
+            "*VT100.translations: #override {}",
+            translations.join("\\n")
+        ));
+
+        if let Some(geometry) = super::x11_geometry(&config.window) {
+            self.args.push(String::from("-geometry"));
+            self.args.push(geometry);
+        }
+        if config.window.fullscreen {
+            self.args.push(String::from("-fullscreen"));
+        } else if config.window.maximized {
+            self.args.push(String::from("-maximized"));
+        }
+        if let Some(padding) = config.window.padding {
+            // Only one internal border width for both directions.
+            self.args.push(String::from("-b"));
+            self.args.push(padding.x.to_string());
+        }
+    }
+}
+
+impl Functions for Xterm {
+    fn create_command(&mut self, config: &Config) -> std::process::Command {
+        self.init_args(config);
+        let mut command = std::process::Command::new(&self.exe_path);
+
+        command.arg("-class");
+        command.arg("glrnvim");
+        // Enable 24-bits colors if xterm is built with it.
+        command.arg("-xrm");
+        command.arg("*directColor: true");
+        command.args(&self.args);
+        command.arg("-e");
+        command.arg(&config.nvim_exe_path);
+        command.args(super::common_args(config));
+        command
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config;
+
+    #[test]
+    fn test_xterm_font_args() {
+        let conf = config::Config {
+            backend: Some(config::Backend::Xterm),
+            font_size: 14.0,
+            fonts: vec!["test_font".into(), "fallback_font".into()],
+            ..Default::default()
+        };
+        let mut xterm = Xterm {
+            exe_path: PathBuf::new(),
+            args: vec![],
+        };
+        xterm.init_args(&conf);
+        assert_eq!(
+            xterm.args,
+            vec![
+                "-fa",
+                "test_font,fallback_font",
+                "-fs",
+                "14",
+                "-xrm",
+                "*faceSize1: 11",
+                "-xrm",
+                "*faceSize2: 12",
+                "-xrm",
+                "*faceSize3: 13",
+                "-xrm",
+                "*faceSize4: 15",
+                "-xrm",
+                "*faceSize5: 16",
+                "-xrm",
+                "*faceSize6: 17",
+                "-xrm",
+                "*VT100.translations: #override Ctrl<Key>z: string(\"\")\\nCtrl<Key>equal: larger-vt-font()\\nCtrl<Key>plus: larger-vt-font()\\nCtrl<Key>minus: smaller-vt-font()\\nCtrl<Key>0: set-vt-font(d)"
+            ]
+        );
+    }
+
+    #[test]
+    fn test_xterm_key_translations() {
+        let conf = config::Config {
+            backend: Some(config::Backend::Xterm),
+            keys: vec![
+                config::KeyBinding {
+                    key: "ctrl+shift+v".parse().unwrap(),
+                    action: config::KeyAction::Paste,
+                },
+                config::KeyBinding {
+                    key: "ctrl+tab".parse().unwrap(),
+                    action: config::KeyAction::Passthrough,
+                },
+                config::KeyBinding {
+                    key: "ctrl+shift+z".parse().unwrap(),
+                    action: config::KeyAction::Passthrough,
+                },
+            ],
+            ..Default::default()
+        };
+        let mut xterm = Xterm {
+            exe_path: PathBuf::new(),
+            args: vec![],
+        };
+        xterm.init_args(&conf);
+        assert_eq!(
+            xterm.args,
+            vec![
+                "-xrm",
+                "*VT100.translations: #override Ctrl Shift<Key>v: insert-selection(CLIPBOARD)\\nCtrl<Key>Tab: insert()\\nCtrl Shift<Key>z: insert()\\nCtrl<Key>z: string(\"\")\\nCtrl<Key>equal: larger-vt-font()\\nCtrl<Key>plus: larger-vt-font()\\nCtrl<Key>minus: smaller-vt-font()\\nCtrl<Key>0: set-vt-font(d)"
+            ]
+        );
+    }
+
+    #[test]
+    fn test_xterm_cursor_args() {
+        let cursor_args = |cursor: config::Cursor| {
+            let conf = config::Config {
+                backend: Some(config::Backend::Xterm),
+                cursor,
+                ..Default::default()
+            };
+            let mut xterm = Xterm {
+                exe_path: PathBuf::new(),
+                args: vec![],
+            };
+            xterm.init_args(&conf);
+            xterm.args
+        };
+        let args = cursor_args(config::Cursor {
+            shape: Some(config::CursorShape::Beam),
+            blink: Some(true),
+            blink_interval: Some(500),
+            ..Default::default()
+        });
+        assert_eq!(
+            args[..7],
+            [
+                "-bc",
+                "-xrm",
+                "*cursorBar: true",
+                "-xrm",
+                "*cursorOnTime: 500",
+                "-xrm",
+                "*cursorOffTime: 500",
+            ]
+        );
+        let args = cursor_args(config::Cursor {
+            shape: Some(config::CursorShape::Underline),
+            blink: Some(false),
+            ..Default::default()
+        });
+        assert_eq!(args[..3], ["+bc", "-xrm", "*cursorUnderLine: true"]);
+    }
+}
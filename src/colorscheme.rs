@@ -0,0 +1,393 @@
+use crate::config::{Color, Colors};
+use crate::error::GlrnvimError;
+use serde_yaml::Mapping;
+use std::path::Path;
+use toml_edit::DocumentMut;
+
+// Load the color scheme from a base16 yaml file, an iTerm2 .itermcolors file or an alacritty
+// theme toml file. The format is identified by the file extension.
+pub fn load(path: &Path) -> Result<Colors, GlrnvimError> {
+    let content = std::fs::read_to_string(path).map_err(|e| {
+        GlrnvimError::new(format!(
+            "Cannot load colorscheme file '{}'. {}",
+            path.display(),
+            e
+        ))
+    })?;
+    let extension = path
+        .extension()
+        .and_then(|e| e.to_str())
+        .unwrap_or_default()
+        .to_ascii_lowercase();
+    let result = match extension.as_str() {
+        "yaml" | "yml" => parse_base16(&content),
+        "itermcolors" => parse_iterm(&content),
+        "toml" => parse_alacritty(&content),
+        _ => Err(GlrnvimError::new(
+            "Unknown colorscheme file type. Supported types are base16 (.yaml, .yml), \
+             iTerm2 (.itermcolors) and alacritty (.toml).",
+        )),
+    };
+    result.map_err(|e| {
+        GlrnvimError::new(format!(
+            "Cannot parse colorscheme file '{}'. {}",
+            path.display(),
+            e
+        ))
+    })
+}
+
+// Build the palette only if all the 16 colors are given.
+fn complete_palette(palette: [Option<Color>; 16]) -> Vec<Color> {
+    if palette.iter().all(|c| c.is_some()) {
+        palette.iter().map(|c| c.unwrap()).collect()
+    } else {
+        if palette.iter().any(|c| c.is_some()) {
+            log::warn!("The colorscheme doesn't have all the 16 ANSI colors. Ignore them.");
+        }
+        Vec::new()
+    }
+}
+
+// See https://github.com/chriskempson/base16/blob/main/styling.md for the meanings of the base16
+// colors. The terminal mapping follows base16-shell.
+fn parse_base16(content: &str) -> Result<Colors, GlrnvimError> {
+    let mapping: Mapping =
+        serde_yaml::from_str(content).map_err(|e| GlrnvimError::new(e.to_string()))?;
+    // The newer tinted-theming schemes put the colors under "palette".
+    let colors = match mapping.get("palette").and_then(|p| p.as_mapping()) {
+        Some(palette) => palette,
+        None => &mapping,
+    };
+    let base = |i: usize| -> Result<Color, GlrnvimError> {
+        let key = format!("base{:02X}", i);
+        match colors.get(&key).and_then(|v| v.as_str()) {
+            Some(v) => v.parse().map_err(|e: String| GlrnvimError::new(e)),
+            None => Err(GlrnvimError::new(format!("'{}' is missing.", key))),
+        }
+    };
+
+    let palette = [
+        0x00, 0x08, 0x0B, 0x0A, 0x0D, 0x0E, 0x0C, 0x05, 0x03, 0x08, 0x0B, 0x0A, 0x0D, 0x0E, 0x0C,
+        0x07,
+    ]
+    .iter()
+    .map(|i| base(*i))
+    .collect::<Result<Vec<_>, _>>()?;
+
+    Ok(Colors {
+        foreground: Some(base(0x05)?),
+        background: Some(base(0x00)?),
+        cursor: Some(base(0x05)?),
+        selection_foreground: Some(base(0x05)?),
+        selection_background: Some(base(0x02)?),
+        palette,
+    })
+}
+
+fn parse_alacritty(content: &str) -> Result<Colors, GlrnvimError> {
+    let doc = content
+        .parse::<DocumentMut>()
+        .map_err(|e| GlrnvimError::new(e.to_string()))?;
+    let colors = match doc.get("colors") {
+        Some(colors) => colors,
+        None => return Err(GlrnvimError::new("'colors' is missing.")),
+    };
+    // Values like "CellForeground" are not colors, just ignore them.
+    let color = |table: &str, key: &str| -> Option<Color> {
+        colors.get(table)?.get(key)?.as_str()?.parse().ok()
+    };
+
+    let mut palette: [Option<Color>; 16] = [None; 16];
+    for (i, name) in crate::backend::ANSI_COLOR_NAMES.iter().enumerate() {
+        palette[i] = color("normal", name);
+        palette[i + 8] = color("bright", name);
+    }
+
+    Ok(Colors {
+        foreground: color("primary", "foreground"),
+        background: color("primary", "background"),
+        cursor: color("cursor", "cursor"),
+        selection_foreground: color("selection", "text"),
+        selection_background: color("selection", "background"),
+        palette: complete_palette(palette),
+    })
+}
+
+#[derive(Debug, PartialEq)]
+enum PlistValue {
+    Dict(Vec<(String, PlistValue)>),
+    Real(f64),
+    Other,
+}
+
+impl PlistValue {
+    fn get(&self, key: &str) -> Option<&PlistValue> {
+        match self {
+            PlistValue::Dict(entries) => entries.iter().find(|(k, _)| k == key).map(|(_, v)| v),
+            _ => None,
+        }
+    }
+
+    fn as_real(&self) -> Option<f64> {
+        match self {
+            PlistValue::Real(v) => Some(*v),
+            _ => None,
+        }
+    }
+}
+
+#[derive(Debug, PartialEq)]
+enum PlistToken {
+    Open(String),
+    Close(String),
+    Text(String),
+}
+
+// A minimal plist tokenizer which is just enough for .itermcolors files.
+fn tokenize_plist(content: &str) -> Vec<PlistToken> {
+    let mut tokens: Vec<PlistToken> = Vec::new();
+    let mut rest = content;
+    while let Some(start) = rest.find('<') {
+        let text = rest[..start].trim();
+        if !text.is_empty() {
+            tokens.push(PlistToken::Text(text.to_string()));
+        }
+        let end = match rest[start..].find('>') {
+            Some(end) => start + end,
+            None => break,
+        };
+        let tag = &rest[start + 1..end];
+        rest = &rest[end + 1..];
+
+        if tag.starts_with('?') || tag.starts_with('!') {
+            // XML declaration, DOCTYPE or comments
+            continue;
+        }
+        let name = tag
+            .trim_start_matches('/')
+            .trim_end_matches('/')
+            .split_whitespace()
+            .next()
+            .unwrap_or_default()
+            .to_string();
+        if tag.starts_with('/') {
+            tokens.push(PlistToken::Close(name));
+        } else if tag.ends_with('/') {
+            tokens.push(PlistToken::Open(name.clone()));
+            tokens.push(PlistToken::Close(name));
+        } else {
+            tokens.push(PlistToken::Open(name));
+        }
+    }
+    tokens
+}
+
+fn parse_plist_value(tokens: &[PlistToken], pos: &mut usize) -> Result<PlistValue, GlrnvimError> {
+    let invalid = || GlrnvimError::new("Invalid plist file.");
+    let name = match tokens.get(*pos) {
+        Some(PlistToken::Open(name)) => name.as_str(),
+        _ => return Err(invalid()),
+    };
+    *pos += 1;
+
+    match name {
+        "dict" => {
+            let mut entries: Vec<(String, PlistValue)> = Vec::new();
+            loop {
+                match tokens.get(*pos) {
+                    Some(PlistToken::Close(n)) if n == "dict" => {
+                        *pos += 1;
+                        return Ok(PlistValue::Dict(entries));
+                    }
+                    Some(PlistToken::Open(n)) if n == "key" => {
+                        *pos += 1;
+                        let mut key = String::new();
+                        if let Some(PlistToken::Text(text)) = tokens.get(*pos) {
+                            key = text.to_string();
+                            *pos += 1;
+                        }
+                        match tokens.get(*pos) {
+                            Some(PlistToken::Close(n)) if n == "key" => *pos += 1,
+                            _ => return Err(invalid()),
+                        }
+                        let value = parse_plist_value(tokens, pos)?;
+                        entries.push((key, value));
+                    }
+                    _ => return Err(invalid()),
+                }
+            }
+        }
+        _ => {
+            let mut value = PlistValue::Other;
+            let mut depth = 0;
+            loop {
+                match tokens.get(*pos) {
+                    Some(PlistToken::Close(n)) if n == name && depth == 0 => {
+                        *pos += 1;
+                        return Ok(value);
+                    }
+                    Some(PlistToken::Open(_)) => depth += 1,
+                    Some(PlistToken::Close(_)) => depth -= 1,
+                    Some(PlistToken::Text(text)) => {
+                        if name == "real" || name == "integer" {
+                            value = PlistValue::Real(text.parse().map_err(|_| invalid())?);
+                        }
+                    }
+                    None => return Err(invalid()),
+                }
+                *pos += 1;
+            }
+        }
+    }
+}
+
+fn parse_iterm(content: &str) -> Result<Colors, GlrnvimError> {
+    let tokens = tokenize_plist(content);
+    let mut pos = match tokens
+        .iter()
+        .position(|t| *t == PlistToken::Open("dict".to_string()))
+    {
+        Some(pos) => pos,
+        None => return Err(GlrnvimError::new("Invalid plist file.")),
+    };
+    let root = parse_plist_value(&tokens, &mut pos)?;
+
+    let color = |key: &str| -> Option<Color> {
+        let dict = root.get(key)?;
+        let component = |name: &str| -> Option<u8> {
+            let v = dict.get(&format!("{} Component", name))?.as_real()?;
+            Some((v.clamp(0.0, 1.0) * 255.0).round() as u8)
+        };
+        Some(Color {
+            r: component("Red")?,
+            g: component("Green")?,
+            b: component("Blue")?,
+        })
+    };
+
+    let mut palette: [Option<Color>; 16] = [None; 16];
+    for (i, c) in palette.iter_mut().enumerate() {
+        *c = color(&format!("Ansi {} Color", i));
+    }
+
+    Ok(Colors {
+        foreground: color("Foreground Color"),
+        background: color("Background Color"),
+        cursor: color("Cursor Color"),
+        selection_foreground: color("Selected Text Color"),
+        selection_background: color("Selection Color"),
+        palette: complete_palette(palette),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_base16() {
+        let content = r#"
+scheme: "Tomorrow Night"
+author: "Chris Kempson (http://chriskempson.com)"
+base00: "1d1f21"
+base01: "282a2e"
+base02: "373b41"
+base03: "969896"
+base04: "b4b7b4"
+base05: "c5c8c6"
+base06: "e0e0e0"
+base07: "ffffff"
+base08: "cc6666"
+base09: "de935f"
+base0A: "f0c674"
+base0B: "b5bd68"
+base0C: "8abeb7"
+base0D: "81a2be"
+base0E: "b294bb"
+base0F: "a3685a"
+"#;
+        let colors = parse_base16(content).unwrap();
+        assert_eq!(colors.foreground.unwrap().to_string(), "#c5c8c6");
+        assert_eq!(colors.background.unwrap().to_string(), "#1d1f21");
+        assert_eq!(colors.selection_background.unwrap().to_string(), "#373b41");
+        assert_eq!(colors.palette.len(), 16);
+        assert_eq!(colors.palette[1].to_string(), "#cc6666");
+        assert_eq!(colors.palette[8].to_string(), "#969896");
+        assert_eq!(colors.palette[15].to_string(), "#ffffff");
+    }
+
+    #[test]
+    fn test_parse_base16_missing_color() {
+        let result = parse_base16("base00: \"1d1f21\"");
+        assert_eq!(result.unwrap_err().to_string(), "'base08' is missing.");
+    }
+
+    #[test]
+    fn test_parse_alacritty() {
+        let content = r##"
+[colors.primary]
+background = "#1d1f21"
+foreground = "0xc5c8c6"
+
+[colors.cursor]
+text = "CellBackground"
+cursor = "CellForeground"
+
+[colors.normal]
+black = "#1d1f21"
+red = "#cc6666"
+"##;
+        let colors = parse_alacritty(content).unwrap();
+        assert_eq!(colors.foreground.unwrap().to_string(), "#c5c8c6");
+        assert_eq!(colors.background.unwrap().to_string(), "#1d1f21");
+        assert_eq!(colors.cursor, None);
+        assert!(colors.palette.is_empty());
+    }
+
+    #[test]
+    fn test_parse_iterm() {
+        let content = r#"<?xml version="1.0" encoding="UTF-8"?>
+<!DOCTYPE plist PUBLIC "-//Apple//DTD PLIST 1.0//EN" "http://www.apple.com/DTDs/PropertyList-1.0.dtd">
+<plist version="1.0">
+<dict>
+	<key>Background Color</key>
+	<dict>
+		<key>Alpha Component</key>
+		<real>1</real>
+		<key>Blue Component</key>
+		<real>0.12941177189350128</real>
+		<key>Color Space</key>
+		<string>sRGB</string>
+		<key>Green Component</key>
+		<real>0.12156862765550613</real>
+		<key>Red Component</key>
+		<real>0.11372549086809158</real>
+	</dict>
+	<key>Bold Font</key>
+	<true/>
+	<key>Foreground Color</key>
+	<dict>
+		<key>Blue Component</key>
+		<real>1</real>
+		<key>Green Component</key>
+		<integer>0</integer>
+		<key>Red Component</key>
+		<real>0.5</real>
+	</dict>
+</dict>
+</plist>
+"#;
+        let colors = parse_iterm(content).unwrap();
+        assert_eq!(colors.background.unwrap().to_string(), "#1d1f21");
+        assert_eq!(colors.foreground.unwrap().to_string(), "#8000ff");
+        assert_eq!(colors.cursor, None);
+        assert!(colors.palette.is_empty());
+    }
+
+    #[test]
+    fn test_load_unknown_type() {
+        let file = tempfile::Builder::new().suffix(".txt").tempfile().unwrap();
+        assert!(load(file.path()).is_err());
+    }
+}
@@ -2,7 +2,10 @@ extern crate serde;
 extern crate serde_yaml;
 
 use serde::Deserialize;
-use std::path::PathBuf;
+use serde_yaml::{Mapping, Value};
+use std::fmt;
+use std::path::{Path, PathBuf};
+use std::str::FromStr;
 
 const NVIM_NAME: &str = "nvim";
 
@@ -14,13 +17,528 @@ pub enum Backend {
     Kitty,
     Wezterm,
     Foot,
+    Ghostty,
+    St,
+    Xterm,
+    Konsole,
+    #[serde(rename = "gnome-terminal")]
+    GnomeTerminal,
+    Kgx,
+    Ptyxis,
+    Rio,
+    Contour,
+    Custom,
 }
 
 #[derive(Debug, PartialEq, Eq, Deserialize)]
+pub struct CustomBackend {
+    // The argv template to start the terminal. See config.yml for the placeholders.
+    #[serde(default)]
+    pub args: Vec<String>,
+}
+
+// A 24-bits color in the "#rrggbb" format.
+#[derive(Debug, PartialEq, Eq, Clone, Copy, Deserialize)]
+#[serde(try_from = "String")]
+pub struct Color {
+    pub r: u8,
+    pub g: u8,
+    pub b: u8,
+}
+
+impl Color {
+    // The "rrggbb" format without the leading '#'.
+    pub fn hex(&self) -> String {
+        format!("{:02x}{:02x}{:02x}", self.r, self.g, self.b)
+    }
+}
+
+impl fmt::Display for Color {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "#{}", self.hex())
+    }
+}
+
+impl FromStr for Color {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let hex = s.trim().trim_start_matches('#');
+        let hex = hex.strip_prefix("0x").unwrap_or(hex);
+        if hex.len() != 6 || !hex.chars().all(|c| c.is_ascii_hexdigit()) {
+            return Err(format!(
+                "invalid color \"{}\", expected the \"#rrggbb\" format",
+                s
+            ));
+        }
+        let channel = |i: usize| u8::from_str_radix(&hex[i..i + 2], 16).unwrap();
+        Ok(Color {
+            r: channel(0),
+            g: channel(2),
+            b: channel(4),
+        })
+    }
+}
+
+impl TryFrom<String> for Color {
+    type Error = String;
+
+    fn try_from(s: String) -> Result<Self, Self::Error> {
+        s.parse()
+    }
+}
+
+#[derive(Debug, Default, PartialEq, Eq, Deserialize)]
+pub struct Colors {
+    pub foreground: Option<Color>,
+    pub background: Option<Color>,
+    pub cursor: Option<Color>,
+    pub selection_foreground: Option<Color>,
+    pub selection_background: Option<Color>,
+    // The 16 ANSI colors. 0-7 are the normal colors, and 8-15 are the bright ones.
+    #[serde(default)]
+    pub palette: Vec<Color>,
+}
+
+impl Colors {
+    pub fn is_empty(&self) -> bool {
+        *self == Colors::default()
+    }
+
+    // Fill the colors which are not set with the ones from the base.
+    pub fn merge(&mut self, base: Colors) {
+        self.foreground = self.foreground.or(base.foreground);
+        self.background = self.background.or(base.background);
+        self.cursor = self.cursor.or(base.cursor);
+        self.selection_foreground = self.selection_foreground.or(base.selection_foreground);
+        self.selection_background = self.selection_background.or(base.selection_background);
+        if self.palette.is_empty() {
+            self.palette = base.palette;
+        }
+    }
+}
+
+#[derive(Debug, PartialEq, Eq, Clone, Copy, Deserialize)]
+pub struct Position {
+    pub x: i32,
+    pub y: i32,
+}
+
+// The padding in pixels. It can be a number for both directions, or { x, y }.
+#[derive(Debug, PartialEq, Eq, Clone, Copy, Deserialize)]
+#[serde(from = "PaddingDef")]
+pub struct Padding {
+    pub x: u16,
+    pub y: u16,
+}
+
+#[derive(Deserialize)]
+#[serde(untagged)]
+enum PaddingDef {
+    All(u16),
+    Xy { x: u16, y: u16 },
+}
+
+impl From<PaddingDef> for Padding {
+    fn from(def: PaddingDef) -> Self {
+        match def {
+            PaddingDef::All(v) => Padding { x: v, y: v },
+            PaddingDef::Xy { x, y } => Padding { x, y },
+        }
+    }
+}
+
+#[derive(Debug, Default, PartialEq, Deserialize)]
+pub struct Window {
+    // The initial window size in cells.
+    pub columns: Option<u16>,
+    pub lines: Option<u16>,
+    #[serde(default)]
+    pub maximized: bool,
+    #[serde(default)]
+    pub fullscreen: bool,
+    // The initial window position in pixels.
+    pub position: Option<Position>,
+    // Show the window title bar and borders.
+    pub decorations: Option<bool>,
+    pub padding: Option<Padding>,
+    // The background opacity from 0.0 to 1.0.
+    pub opacity: Option<f32>,
+}
+
+impl Window {
+    // The initial window size in cells as (columns, lines).
+    pub fn size(&self) -> Option<(u16, u16)> {
+        Some((self.columns?, self.lines?))
+    }
+}
+
+#[derive(Debug, PartialEq, Eq, Clone, Copy, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum CursorShape {
+    Block,
+    Beam,
+    Underline,
+}
+
+#[derive(Debug, Default, PartialEq, Eq, Deserialize)]
+pub struct Cursor {
+    pub shape: Option<CursorShape>,
+    pub blink: Option<bool>,
+    // The blink interval in milliseconds.
+    pub blink_interval: Option<u32>,
+    // Same as colors.cursor, with higher priority.
+    pub color: Option<Color>,
+}
+
+impl Cursor {
+    // Whether any of the shape and the blinking is set. The color is handled by Colors.
+    pub fn has_style(&self) -> bool {
+        self.shape.is_some() || self.blink.is_some() || self.blink_interval.is_some()
+    }
+}
+
+// An entry of fonts. It can be a family name, or { family, size, style, unicode_ranges }.
+#[derive(Debug, PartialEq, Clone, Deserialize)]
+#[serde(try_from = "FontSpecDef")]
+pub struct FontSpec {
+    pub family: String,
+    // The size in points. font_size is used if it is not set.
+    pub size: Option<f64>,
+    // The style name in the font, e.g. "Medium".
+    pub style: Option<String>,
+    // The unicode ranges to use the font for, e.g. "U+4E00-U+9FFF". Only used by kitty.
+    pub unicode_ranges: Vec<String>,
+}
+
+impl FontSpec {
+    // The size of the font, or the given default size if it is not set.
+    pub fn size_or(&self, default: f64) -> f64 {
+        self.size.unwrap_or(default)
+    }
+}
+
+#[derive(Deserialize)]
+#[serde(untagged)]
+enum FontSpecDef {
+    // An empty entry is null, which is removed when parsing.
+    Family(Option<String>),
+    Spec {
+        family: String,
+        size: Option<f64>,
+        style: Option<String>,
+        #[serde(default)]
+        unicode_ranges: Vec<String>,
+    },
+}
+
+impl TryFrom<FontSpecDef> for FontSpec {
+    type Error = String;
+
+    fn try_from(def: FontSpecDef) -> Result<Self, Self::Error> {
+        let spec = match def {
+            FontSpecDef::Family(family) => FontSpec::from(family.unwrap_or_default().as_str()),
+            FontSpecDef::Spec {
+                family,
+                size,
+                style,
+                unicode_ranges,
+            } => FontSpec {
+                family,
+                size,
+                style,
+                unicode_ranges,
+            },
+        };
+        if let Some(size) = spec.size.filter(|s| !s.is_finite() || *s <= 0.0) {
+            return Err(format!(
+                "invalid font size {} of \"{}\", expected a positive number",
+                size, spec.family
+            ));
+        }
+        for range in &spec.unicode_ranges {
+            if !is_unicode_range(range) {
+                return Err(format!(
+                    "invalid unicode range \"{}\", expected the \"U+4E00-U+9FFF\" or \"U+E0A0\" format",
+                    range
+                ));
+            }
+        }
+        Ok(spec)
+    }
+}
+
+impl From<&str> for FontSpec {
+    fn from(family: &str) -> Self {
+        FontSpec {
+            family: family.to_string(),
+            size: None,
+            style: None,
+            unicode_ranges: Vec::new(),
+        }
+    }
+}
+
+impl fmt::Display for FontSpec {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.family)
+    }
+}
+
+// Check if the string is a code point "U+XXXX" or a range "U+XXXX-U+YYYY".
+fn is_unicode_range(s: &str) -> bool {
+    let code_point = |s: &str| {
+        let hex = s.strip_prefix("U+").or_else(|| s.strip_prefix("u+"))?;
+        if hex.is_empty() || hex.len() > 6 {
+            return None;
+        }
+        u32::from_str_radix(hex, 16).ok().filter(|c| *c <= 0x10ffff)
+    };
+    match s.split_once('-') {
+        Some((start, end)) => match (code_point(start), code_point(end)) {
+            (Some(start), Some(end)) => start <= end,
+            _ => false,
+        },
+        None => code_point(s).is_some(),
+    }
+}
+
+#[derive(Debug, Default, Clone, PartialEq, Eq, Deserialize)]
+pub struct FontFace {
+    pub family: Option<String>,
+    // The style name in the font, e.g. "Medium Italic".
+    pub style: Option<String>,
+}
+
+#[derive(Debug, Default, PartialEq, Eq, Deserialize)]
+pub struct Font {
+    // normal.family is the primary font. It is moved to the front of Config::fonts when parsing.
+    pub normal: Option<FontFace>,
+    pub bold: Option<FontFace>,
+    pub italic: Option<FontFace>,
+    pub bold_italic: Option<FontFace>,
+    // OpenType features in the HarfBuzz format, e.g. "-liga" or "+zero".
+    #[serde(default)]
+    pub features: Vec<String>,
+}
+
+impl Font {
+    pub fn normal_style(&self) -> Option<&str> {
+        self.normal.as_ref().and_then(|f| f.style.as_deref())
+    }
+
+    // The bold, italic and bold italic faces with the config keys of their names.
+    pub fn faces(&self) -> [(&'static str, Option<&FontFace>); 3] {
+        [
+            ("bold", self.bold.as_ref()),
+            ("italic", self.italic.as_ref()),
+            ("bold_italic", self.bold_italic.as_ref()),
+        ]
+    }
+}
+
+// The named keys which can be used in keys. Other keys are letters and digits.
+const KEY_NAMES: &[&str] = &[
+    "tab",
+    "enter",
+    "escape",
+    "space",
+    "backspace",
+    "delete",
+    "insert",
+    "home",
+    "end",
+    "page_up",
+    "page_down",
+    "up",
+    "down",
+    "left",
+    "right",
+    "plus",
+    "minus",
+    "equal",
+    "f1",
+    "f2",
+    "f3",
+    "f4",
+    "f5",
+    "f6",
+    "f7",
+    "f8",
+    "f9",
+    "f10",
+    "f11",
+    "f12",
+];
+
+// A key with the modifiers, e.g. "ctrl+shift+c".
+#[derive(Debug, PartialEq, Eq, Clone, Deserialize)]
+#[serde(try_from = "String")]
+pub struct KeyChord {
+    pub ctrl: bool,
+    pub shift: bool,
+    pub alt: bool,
+    pub super_key: bool,
+    // A lowercase letter or digit, or one of KEY_NAMES.
+    pub key: String,
+}
+
+impl FromStr for KeyChord {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let err = || {
+            format!(
+                "invalid key \"{}\", expected modifiers and a key joined with '+', e.g. \"ctrl+shift+c\"",
+                s
+            )
+        };
+        let lowercase = s.trim().to_lowercase();
+        // "ctrl++" is the same as "ctrl+plus".
+        let (mods, key) = match lowercase.strip_suffix("++") {
+            Some(mods) => (mods, "+"),
+            None => lowercase
+                .rsplit_once('+')
+                .unwrap_or(("", lowercase.as_str())),
+        };
+        let key = match key {
+            "+" => "plus",
+            "-" => "minus",
+            "=" | "equals" => "equal",
+            "return" => "enter",
+            "esc" => "escape",
+            key => key,
+        };
+        let is_char = key.len() == 1 && key.chars().all(|c| c.is_ascii_alphanumeric());
+        if !is_char && !KEY_NAMES.contains(&key) {
+            return Err(err());
+        }
+        let mut chord = KeyChord {
+            ctrl: false,
+            shift: false,
+            alt: false,
+            super_key: false,
+            key: key.to_string(),
+        };
+        for m in mods.split('+').filter(|m| !m.is_empty()) {
+            match m {
+                "ctrl" | "control" => chord.ctrl = true,
+                "shift" => chord.shift = true,
+                "alt" | "meta" | "opt" | "option" => chord.alt = true,
+                "super" | "cmd" | "command" => chord.super_key = true,
+                _ => return Err(err()),
+            }
+        }
+        Ok(chord)
+    }
+}
+
+impl TryFrom<String> for KeyChord {
+    type Error = String;
+
+    fn try_from(s: String) -> Result<Self, Self::Error> {
+        s.parse()
+    }
+}
+
+impl fmt::Display for KeyChord {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for (m, name) in [
+            (self.ctrl, "ctrl+"),
+            (self.shift, "shift+"),
+            (self.alt, "alt+"),
+            (self.super_key, "super+"),
+        ] {
+            if m {
+                write!(f, "{}", name)?;
+            }
+        }
+        write!(f, "{}", self.key)
+    }
+}
+
+#[derive(Debug, PartialEq, Eq, Clone, Copy, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum KeyAction {
+    // The terminal swallows the key, and nvim doesn't receive it.
+    Unbind,
+    // The terminal doesn't handle the key, and sends it to nvim.
+    Passthrough,
+    // The terminal sends the key to nvim in the CSI u encoding, which tells e.g. ctrl+shift+c from
+    // ctrl+c. Only used by the clipboard keys.
+    #[serde(skip_deserializing)]
+    CsiU,
+    Copy,
+    Paste,
+    IncreaseFontSize,
+    DecreaseFontSize,
+    ResetFontSize,
+    ToggleFullscreen,
+}
+
+#[derive(Debug, PartialEq, Eq, Clone, Deserialize)]
+pub struct KeyBinding {
+    pub key: KeyChord,
+    pub action: KeyAction,
+}
+
+// The keys to copy and paste through the + register of nvim. Unlike the terminal's copy, which takes
+// the text on the screen, the line numbers and the sign column are not copied.
+#[derive(Debug, PartialEq, Eq, Clone, Default, Deserialize)]
+pub struct Clipboard {
+    pub copy: Option<KeyChord>,
+    pub paste: Option<KeyChord>,
+}
+
+impl Clipboard {
+    pub fn keys(&self) -> impl Iterator<Item = &KeyChord> {
+        self.copy.iter().chain(self.paste.iter())
+    }
+}
+
+// Accept both integers and fractional numbers, e.g. 12 or 11.5.
+fn deserialize_font_size<'de, D>(deserializer: D) -> Result<f64, D::Error>
+where
+    D: serde::Deserializer<'de>,
+{
+    struct FontSizeVisitor;
+
+    impl serde::de::Visitor<'_> for FontSizeVisitor {
+        type Value = f64;
+
+        fn expecting(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+            formatter.write_str("a font size in points, e.g. 12 or 11.5")
+        }
+
+        fn visit_f64<E: serde::de::Error>(self, v: f64) -> Result<f64, E> {
+            if !v.is_finite() || v < 0.0 {
+                return Err(E::custom(format!(
+                    "invalid font size {}, expected a non-negative number (0 = unset)",
+                    v
+                )));
+            }
+            Ok(v)
+        }
+
+        fn visit_i64<E: serde::de::Error>(self, v: i64) -> Result<f64, E> {
+            self.visit_f64(v as f64)
+        }
+
+        fn visit_u64<E: serde::de::Error>(self, v: u64) -> Result<f64, E> {
+            self.visit_f64(v as f64)
+        }
+    }
+
+    deserializer.deserialize_any(FontSizeVisitor)
+}
+
+#[derive(Debug, PartialEq, Deserialize)]
 pub struct Config {
     #[serde(skip)]
     pub fork: bool,
     pub backend: Option<Backend>,
+    #[serde(default)]
+    pub backend_preference: Vec<Backend>,
     // TODO: this config option is deprecated, will be removed in the future
     pub exe_path: Option<String>,
     pub term_exe_path: Option<String>,
@@ -30,11 +548,36 @@ pub struct Config {
     #[serde(default)]
     pub load_term_conf: bool,
     #[serde(default)]
-    pub fonts: Vec<String>,
+    pub fonts: Vec<FontSpec>,
+    // 0 means the font size is not set.
+    #[serde(default, deserialize_with = "deserialize_font_size")]
+    pub font_size: f64,
     #[serde(default)]
-    pub font_size: u8,
+    pub font: Font,
+    // The extra space between lines in pixels. It can be negative.
+    pub line_height: Option<i16>,
+    // The extra space between characters in pixels. It can be negative.
+    pub letter_spacing: Option<i16>,
     #[serde(default)]
     pub omit_term_stderr: bool,
+    // Create the dedicated glrnvim profile of gnome-terminal in dconf.
+    #[serde(default)]
+    pub gnome_terminal_profile: bool,
+    pub custom_backend: Option<CustomBackend>,
+    #[serde(default)]
+    pub colors: Colors,
+    pub colorscheme_file: Option<String>,
+    #[serde(default)]
+    pub window: Window,
+    #[serde(default)]
+    pub cursor: Cursor,
+    #[serde(default)]
+    pub keys: Vec<KeyBinding>,
+    // Remove all the default shortcuts of the terminal, except the ones in keys.
+    #[serde(default)]
+    pub passthrough_all_keys: bool,
+    #[serde(default)]
+    pub clipboard: Clipboard,
 }
 
 impl Default for Config {
@@ -42,32 +585,232 @@ impl Default for Config {
         Self {
             fork: false,
             backend: None,
+            backend_preference: Vec::new(),
             nvim_exe_path: NVIM_NAME.to_owned(),
             exe_path: None,
             term_exe_path: None,
             term_config_path: None,
             fonts: Vec::new(),
-            font_size: 0,
+            font_size: 0.0,
+            font: Font::default(),
+            line_height: None,
+            letter_spacing: None,
             load_term_conf: false,
             omit_term_stderr: false,
+            gnome_terminal_profile: false,
+            custom_backend: None,
+            colors: Colors::default(),
+            colorscheme_file: None,
+            window: Window::default(),
+            cursor: Cursor::default(),
+            keys: Vec::new(),
+            passthrough_all_keys: false,
+            clipboard: Clipboard::default(),
         }
     }
 }
 
-pub fn parse(path: PathBuf) -> Config {
-    let file = std::fs::File::open(path).unwrap();
-    let reader = std::io::BufReader::new(file);
-    let mut config: Config = match serde_yaml::from_reader(reader) {
-        Ok(c) => c,
-        Err(e) => {
-            panic!("{}", e.to_string())
+pub const PROJECT_CONFIG_NAME: &str = ".glrnvim.yml";
+pub const ENV_PREFIX: &str = "GLRNVIM_";
+
+// The only options which can be set by a project config. It comes with the files being edited, so
+// the options which run an executable, read a file or write a persistent state are left out.
+const PROJECT_ALLOWED_KEYS: &[&str] = &[
+    "fonts",
+    "font_size",
+    "font",
+    "line_height",
+    "letter_spacing",
+    "colors",
+    "window",
+    "cursor",
+    "keys",
+    "passthrough_all_keys",
+    "clipboard",
+];
+
+// The nearest project config in the directory or its ancestors.
+pub fn find_project_file(dir: &Path) -> Option<PathBuf> {
+    dir.ancestors()
+        .map(|d| d.join(PROJECT_CONFIG_NAME))
+        .find(|p| p.is_file())
+}
+
+// Merge the config files, the project config and the GLRNVIM_* environment variables in the order
+// of priority from low to high.
+pub fn load(files: &[PathBuf], project_file: Option<&Path>, vars: &[(String, String)]) -> Config {
+    let mut merged = Value::Mapping(Mapping::new());
+    for path in files {
+        match read_layer(path) {
+            Ok(layer) => merge_yaml(&mut merged, layer),
+            Err(e) => panic!("{}", e),
+        }
+    }
+    // A broken project config doesn't stop glrnvim from starting in the whole project.
+    if let Some(path) = project_file {
+        match read_layer(path) {
+            Ok(mut layer) => {
+                if let Value::Mapping(mapping) = &mut layer {
+                    mapping.retain(|key, _| {
+                        let key = key.as_str().unwrap_or_default();
+                        let allowed = PROJECT_ALLOWED_KEYS.contains(&key);
+                        if !allowed {
+                            log::warn!("{} cannot be set in '{}'.", key, path.display());
+                        }
+                        allowed
+                    });
+                }
+                merge_yaml(&mut merged, layer);
+            }
+            Err(e) => log::warn!("{}. The project config is skipped.", e),
         }
+    }
+    let layer = env_layer(vars);
+    if let Err(e) = serde_yaml::from_value::<Config>(layer.clone()) {
+        panic!("environment variables: {}", e)
+    }
+    merge_yaml(&mut merged, layer);
+
+    match serde_yaml::from_value(merged) {
+        Ok(config) => check(config),
+        Err(e) => panic!("{}", e),
+    }
+}
+
+// A config file as a YAML value. An empty file is an empty mapping. The errors start with the path
+// of the file.
+fn read_layer(path: &Path) -> Result<Value, String> {
+    let error = |e: &dyn fmt::Display| format!("{}: {}", path.display(), e);
+    let content = std::fs::read_to_string(path).map_err(|e| error(&e))?;
+    let mut layer: Value = match serde_yaml::from_str(&content) {
+        Ok(Value::Null) => return Ok(Value::Mapping(Mapping::new())),
+        Ok(layer) => layer,
+        Err(e) => return Err(error(&e)),
     };
+    // Parsed as Config as well for the errors with the locations in the file.
+    serde_yaml::from_str::<Config>(&content).map_err(|e| error(&e))?;
 
+    // A relative colorscheme_file is relative to the directory of the config file.
+    if let Some(Value::String(colorscheme_file)) = layer.get_mut("colorscheme_file") {
+        let expanded = shellexpand::full(colorscheme_file).map_err(|e| error(&e))?;
+        if let Some(dir) = path.parent() {
+            *colorscheme_file = dir.join(expanded.as_ref()).display().to_string();
+        }
+    }
+    Ok(layer)
+}
+
+// GLRNVIM_FONT_SIZE=14 is the same as font_size: 14, and GLRNVIM_WINDOW__OPACITY=0.9 is the same as
+// window.opacity: 0.9. The values are parsed as YAML, e.g. GLRNVIM_FONTS="[Hack, Noto Color Emoji]".
+fn env_layer(vars: &[(String, String)]) -> Value {
+    let mut layer = Value::Mapping(Mapping::new());
+    for (name, value) in vars {
+        let Some(name) = name.strip_prefix(ENV_PREFIX) else {
+            continue;
+        };
+        let value = serde_yaml::from_str(value).unwrap_or_else(|_| Value::from(value.as_str()));
+        let mut var = value;
+        for key in name.to_lowercase().rsplit("__") {
+            let mut mapping = Mapping::new();
+            mapping.insert(Value::from(key), var);
+            var = Value::Mapping(mapping);
+        }
+        merge_yaml(&mut layer, var);
+    }
+    layer
+}
+
+// The mappings are merged recursively, and the other values are replaced.
+fn merge_yaml(base: &mut Value, layer: Value) {
+    match (base, layer) {
+        (Value::Mapping(base), Value::Mapping(layer)) => {
+            for (key, value) in layer {
+                if let Some(base_value) = base.get_mut(&key) {
+                    merge_yaml(base_value, value);
+                } else {
+                    base.insert(key, value);
+                }
+            }
+        }
+        (base, layer) => *base = layer,
+    }
+}
+
+// Validate the merged config and fill the options derived from others.
+fn check(mut config: Config) -> Config {
     if config.backend.is_none() && config.term_exe_path.is_some() {
         panic!("term_exe_path requires a backend key")
     }
 
+    if config.backend == Some(Backend::Custom)
+        && config
+            .custom_backend
+            .as_ref()
+            .is_none_or(|c| c.args.is_empty())
+    {
+        panic!("custom backend requires custom_backend.args")
+    }
+
+    if !config.colors.palette.is_empty() && config.colors.palette.len() != 16 {
+        panic!(
+            "colors.palette requires 16 colors, but {} are given",
+            config.colors.palette.len()
+        )
+    }
+
+    if config.window.columns.is_some() != config.window.lines.is_some() {
+        panic!("window.columns and window.lines must be set together")
+    }
+    if config.window.columns == Some(0) || config.window.lines == Some(0) {
+        panic!("window.columns and window.lines must be greater than 0")
+    }
+
+    if config
+        .window
+        .opacity
+        .is_some_and(|o| !(0.0..=1.0).contains(&o))
+    {
+        panic!("window.opacity must be between 0.0 and 1.0")
+    }
+
+    if let Some(colorscheme_file) = config.colorscheme_file.as_ref() {
+        // The relative paths in the config files have been resolved by read_layer.
+        let expanded = shellexpand::full(colorscheme_file)
+            .unwrap_or_else(|e| panic!("colorscheme_file: {}", e));
+        match crate::colorscheme::load(Path::new(expanded.as_ref())) {
+            // The colors in the config have higher priority.
+            Ok(colors) => config.colors.merge(colors),
+            Err(e) => panic!("{}", e),
+        }
+    }
+
+    if config.cursor.blink_interval == Some(0) {
+        panic!("cursor.blink_interval must be greater than 0")
+    }
+    if config.cursor.color.is_some() {
+        config.colors.cursor = config.cursor.color;
+    }
+
+    for (i, binding) in config.keys.iter().enumerate() {
+        if config.keys[..i].iter().any(|b| b.key == binding.key) {
+            panic!("keys: {} is bound more than once", binding.key)
+        }
+    }
+    for key in config.clipboard.keys() {
+        // nvim can only map the keys encoded by the terminal, which are the letters and digits.
+        if key.key.len() != 1 || !(key.ctrl || key.alt || key.super_key) {
+            panic!(
+                "clipboard: {} must be a letter or a digit with the ctrl, alt or super modifier",
+                key
+            )
+        }
+        if config.keys.iter().any(|b| b.key == *key)
+            || config.clipboard.copy == config.clipboard.paste
+        {
+            panic!("keys: {} is bound more than once", key)
+        }
+    }
+
     if config.nvim_exe_path.is_empty() {
         config.nvim_exe_path = NVIM_NAME.to_owned()
     }
@@ -77,16 +820,88 @@ pub fn parse(path: PathBuf) -> Config {
         config.term_exe_path = config.exe_path.clone()
     }
 
+    if let Some(family) = config.font.normal.as_mut().and_then(|f| f.family.take()) {
+        config.fonts.insert(0, FontSpec::from(family.as_str()));
+    }
     config.fonts = config
         .fonts
         .into_iter()
-        .filter(|s| !s.is_empty() && s != "~")
+        .filter(|f| !f.family.is_empty() && f.family != "~")
         .collect::<Vec<_>>();
+    // The font names are written into the configs and the Lua expressions of the terminals as they
+    // are, so they cannot break out of the quotes or the lines.
+    let mut font_names: Vec<&String> = Vec::new();
+    for font in &config.fonts {
+        font_names.push(&font.family);
+        font_names.extend(&font.style);
+    }
+    let faces = config.font.faces().into_iter().filter_map(|(_, face)| face);
+    for face in config.font.normal.iter().chain(faces) {
+        font_names.extend(&face.family);
+        font_names.extend(&face.style);
+    }
+    font_names.extend(&config.font.features);
+    for name in font_names {
+        if name
+            .chars()
+            .any(|c| c == '"' || c == '\\' || c.is_control())
+        {
+            panic!(
+                "font: \"{}\" must not contain quotes, backslashes or control characters",
+                name.escape_debug()
+            )
+        }
+    }
+
+    // The size and the style of the major font are the same as font_size and font.normal.style.
+    if let Some(first) = config.fonts.first() {
+        if let Some(size) = first.size {
+            config.font_size = size;
+        }
+        if let Some(style) = first.style.as_ref() {
+            let normal = config.font.normal.get_or_insert_with(FontFace::default);
+            normal.style.get_or_insert_with(|| style.to_string());
+        }
+    }
 
     config
 }
 
 impl Config {
+    // The key bindings of keys and clipboard, with the default ones which are not set in them.
+    pub fn key_bindings(&self) -> Vec<KeyBinding> {
+        let mut keys = self.keys.clone();
+        keys.extend(self.clipboard.keys().map(|key| KeyBinding {
+            key: key.clone(),
+            action: KeyAction::CsiU,
+        }));
+        // ctrl+z is always unbound, since suspending nvim leaves a blank window. The font zoom keys
+        // work like in the GUI applications unless all the keys are passed through.
+        let mut defaults = vec![("ctrl+z", KeyAction::Unbind)];
+        if !self.passthrough_all_keys {
+            defaults.extend([
+                ("ctrl+equal", KeyAction::IncreaseFontSize),
+                ("ctrl+plus", KeyAction::IncreaseFontSize),
+                ("ctrl+minus", KeyAction::DecreaseFontSize),
+                ("ctrl+0", KeyAction::ResetFontSize),
+            ]);
+        }
+        let mut bindings: Vec<KeyBinding> = defaults
+            .into_iter()
+            .map(|(key, action)| KeyBinding {
+                key: key.parse().unwrap(),
+                action,
+            })
+            .filter(|d| !keys.iter().any(|b| b.key == d.key))
+            .collect();
+        bindings.extend(keys);
+        bindings
+    }
+
+    pub fn font_families(&self) -> Vec<&str> {
+        self.fonts.iter().map(|f| f.family.as_str()).collect()
+    }
+
     pub fn should_omit_stderr(&self) -> bool {
         if log::log_enabled!(log::Level::Debug) {
             return false;
@@ -108,6 +923,10 @@ mod tests {
         path: PathBuf,
     }
 
+    fn parse(path: PathBuf) -> Config {
+        load(&[path], None, &[])
+    }
+
     fn make_cfg_file(content: &str) -> TempConfFile {
         // Create a directory inside of `std::env::temp_dir()`.
         let dir = tempdir().unwrap();
@@ -138,14 +957,231 @@ fonts:
             .path,
         );
         assert_eq!(config.fonts.len(), 2);
-        assert_eq!(config.fonts, vec!["MonoAbc ff", "ac"]);
+        assert_eq!(config.font_families(), vec!["MonoAbc ff", "ac"]);
+    }
+
+    #[test]
+    fn test_parse_font_unicode_ranges() {
+        let config = parse(
+            make_cfg_file(
+                r#"
+fonts:
+  - Iosevka
+  - family: Noto Sans CJK JP
+    unicode_ranges: ["U+4E00-U+9FFF", "u+3000"]
+"#,
+            )
+            .path,
+        );
+        assert_eq!(config.fonts[0], FontSpec::from("Iosevka"));
+        assert_eq!(config.fonts[1].family, "Noto Sans CJK JP");
+        assert_eq!(
+            config.fonts[1].unicode_ranges,
+            vec!["U+4E00-U+9FFF", "u+3000"]
+        );
+    }
+
+    #[test]
+    fn test_parse_font_entry_size() {
+        let config = parse(
+            make_cfg_file(
+                r#"
+font_size: 12
+fonts:
+  - family: Iosevka
+    size: 13.5
+    style: Medium
+  - family: Symbols Nerd Font
+    size: 10
+"#,
+            )
+            .path,
+        );
+        assert_eq!(config.font_size, 13.5);
+        assert_eq!(config.font.normal_style(), Some("Medium"));
+        assert_eq!(config.fonts[1].size_or(config.font_size), 10.0);
+    }
+
+    #[test]
+    #[should_panic(expected = "invalid unicode range \"U+9FFF-U+4E00\"")]
+    fn test_parse_invalid_unicode_range() {
+        parse(
+            make_cfg_file(
+                r#"
+fonts:
+  - family: Noto Sans CJK JP
+    unicode_ranges: ["U+9FFF-U+4E00"]
+"#,
+            )
+            .path,
+        );
+    }
+
+    #[test]
+    fn test_parse_font() {
+        let config = parse(
+            make_cfg_file(
+                r#"
+fonts:
+  - Fallback Mono
+font:
+  normal:
+    family: Iosevka
+    style: Medium
+  italic:
+    family: Iosevka Etoile
+  bold_italic:
+    style: Heavy Italic
+  features: ["-liga", "+zero"]
+"#,
+            )
+            .path,
+        );
+        assert_eq!(config.font_families(), vec!["Iosevka", "Fallback Mono"]);
+        assert_eq!(config.font.normal_style(), Some("Medium"));
+        assert_eq!(config.font.bold, None);
+        assert_eq!(
+            config.font.italic,
+            Some(FontFace {
+                family: Some("Iosevka Etoile".to_string()),
+                style: None,
+            })
+        );
+        assert_eq!(
+            config.font.bold_italic,
+            Some(FontFace {
+                family: None,
+                style: Some("Heavy Italic".to_string()),
+            })
+        );
+        assert_eq!(config.font.features, vec!["-liga", "+zero"]);
+    }
+
+    #[test]
+    fn test_parse_spacing() {
+        let config = parse(make_cfg_file("line_height: 2\nletter_spacing: -1").path);
+        assert_eq!(config.line_height, Some(2));
+        assert_eq!(config.letter_spacing, Some(-1));
+    }
+
+    #[test]
+    fn test_parse_keys() {
+        let config = parse(
+            make_cfg_file(
+                r#"
+keys:
+  - key: ctrl+shift+c
+    action: copy
+  - key: Ctrl+=
+    action: passthrough
+  - key: ctrl++
+    action: increase_font_size
+"#,
+            )
+            .path,
+        );
+        assert_eq!(config.keys.len(), 3);
+        assert_eq!(config.keys[0].key.to_string(), "ctrl+shift+c");
+        assert_eq!(config.keys[0].action, KeyAction::Copy);
+        assert_eq!(config.keys[1].key.to_string(), "ctrl+equal");
+        assert_eq!(config.keys[2].key.to_string(), "ctrl+plus");
+
+        // ctrl+z is unbound and the font zoom keys are bound by default, unless they are set.
+        let bindings = config.key_bindings();
+        assert_eq!(bindings.len(), 6);
+        assert_eq!(bindings[0].key.to_string(), "ctrl+z");
+        assert_eq!(bindings[0].action, KeyAction::Unbind);
+        assert_eq!(bindings[1].key.to_string(), "ctrl+minus");
+        assert_eq!(bindings[1].action, KeyAction::DecreaseFontSize);
+        assert_eq!(bindings[2].key.to_string(), "ctrl+0");
+        assert_eq!(bindings[2].action, KeyAction::ResetFontSize);
+
+        let config = parse(
+            make_cfg_file(
+                r#"
+keys: [{key: ctrl+z, action: passthrough}]
+passthrough_all_keys: true
+"#,
+            )
+            .path,
+        );
+        assert_eq!(config.key_bindings(), config.keys);
+    }
+
+    #[test]
+    #[should_panic(expected = "invalid key \"ctrl+hyper+c\"")]
+    fn test_parse_invalid_key() {
+        parse(make_cfg_file("keys: [{key: ctrl+hyper+c, action: copy}]").path);
+    }
+
+    #[test]
+    #[should_panic(expected = "keys: ctrl+shift+c is bound more than once")]
+    fn test_parse_duplicate_keys() {
+        parse(
+            make_cfg_file(
+                "keys: [{key: ctrl+shift+c, action: copy}, {key: shift+ctrl+c, action: unbind}]",
+            )
+            .path,
+        );
+    }
+
+    #[test]
+    fn test_parse_clipboard() {
+        let config = parse(
+            make_cfg_file(
+                r#"
+clipboard:
+  copy: ctrl+shift+c
+  paste: Ctrl+Shift+V
+"#,
+            )
+            .path,
+        );
+        assert_eq!(config.clipboard.copy, Some("ctrl+shift+c".parse().unwrap()));
+        assert_eq!(
+            config.clipboard.paste,
+            Some("ctrl+shift+v".parse().unwrap())
+        );
+        let bindings = config.key_bindings();
+        assert_eq!(bindings[bindings.len() - 1].key.to_string(), "ctrl+shift+v");
+        assert_eq!(bindings[bindings.len() - 1].action, KeyAction::CsiU);
+    }
+
+    #[test]
+    #[should_panic(expected = "clipboard: ctrl+f1 must be a letter or a digit")]
+    fn test_parse_invalid_clipboard_key() {
+        parse(make_cfg_file("clipboard: {copy: ctrl+f1}").path);
+    }
+
+    #[test]
+    #[should_panic(expected = "keys: ctrl+shift+v is bound more than once")]
+    fn test_parse_duplicate_clipboard_key() {
+        parse(
+            make_cfg_file(
+                r#"
+keys: [{key: ctrl+shift+v, action: paste}]
+clipboard: {paste: ctrl+shift+v}
+"#,
+            )
+            .path,
+        );
     }
 
     #[test]
     fn test_parse_font_size() {
         let config = parse(make_cfg_file("font_size: 15").path);
-        assert_eq!(config.font_size, 15);
+        assert_eq!(config.font_size, 15.0);
         assert!(config.fonts.is_empty());
+
+        let config = parse(make_cfg_file("font_size: 11.5").path);
+        assert_eq!(config.font_size, 11.5);
+
+        let config = parse(make_cfg_file("font_size: 300").path);
+        assert_eq!(config.font_size, 300.0);
+
+        // 0 leaves the font size unset.
+        let config = parse(make_cfg_file("font_size: 0").path);
+        assert_eq!(config.font_size, 0.0);
     }
 
     #[test]
@@ -182,11 +1218,310 @@ fonts:
         parse(make_cfg_file("term_exe_path: /path/to/kitty").path);
     }
 
+    #[test]
+    fn test_parse_backend_preference() {
+        let config = parse(make_cfg_file("backend_preference: [foot, kitty, gnome-terminal]").path);
+        assert_eq!(config.backend, None);
+        assert_eq!(
+            config.backend_preference,
+            vec![Backend::Foot, Backend::Kitty, Backend::GnomeTerminal]
+        );
+    }
+
+    #[test]
+    fn test_parse_colors() {
+        let config = parse(
+            make_cfg_file(
+                r##"
+colors:
+  foreground: "#C5c8c6"
+  background: "1d1f21"
+  palette: ["#000000", "#cc6666", "#b5bd68", "#f0c674", "#81a2be", "#b294bb", "#8abeb7", "#c5c8c6",
+            "#666666", "#d54e53", "#b9ca4a", "#e7c547", "#7aa6da", "#c397d8", "#70c0b1", "#eaeaea"]
+"##,
+            )
+            .path,
+        );
+        assert_eq!(
+            config.colors.foreground,
+            Some(Color {
+                r: 0xc5,
+                g: 0xc8,
+                b: 0xc6
+            })
+        );
+        assert_eq!(config.colors.background.unwrap().to_string(), "#1d1f21");
+        assert_eq!(config.colors.cursor, None);
+        assert_eq!(config.colors.palette.len(), 16);
+        assert_eq!(config.colors.palette[9].hex(), "d54e53");
+    }
+
+    #[test]
+    #[should_panic(expected = "invalid color \"#12345\", expected the \"#rrggbb\" format")]
+    fn test_parse_invalid_color() {
+        parse(make_cfg_file("colors:\n  cursor: \"#12345\"").path);
+    }
+
+    #[test]
+    #[should_panic(expected = "colors.palette requires 16 colors, but 1 are given")]
+    fn test_parse_invalid_palette() {
+        parse(make_cfg_file("colors:\n  palette: [\"#123456\"]").path);
+    }
+
+    #[test]
+    fn test_parse_colorscheme_file() {
+        let dir = tempdir().unwrap();
+        let theme_path = dir.path().join("theme.toml");
+        std::fs::write(
+            &theme_path,
+            "[colors.primary]\nforeground = \"#c5c8c6\"\nbackground = \"#1d1f21\"\n",
+        )
+        .unwrap();
+        let config_path = dir.path().join("config.yml");
+        std::fs::write(
+            &config_path,
+            "colorscheme_file: theme.toml\ncolors:\n  background: \"#000000\"\n",
+        )
+        .unwrap();
+        let config = parse(config_path);
+        assert_eq!(config.colors.foreground.unwrap().to_string(), "#c5c8c6");
+        assert_eq!(config.colors.background.unwrap().to_string(), "#000000");
+    }
+
+    #[test]
+    #[should_panic(expected = "Cannot load colorscheme file")]
+    fn test_parse_non_exist_colorscheme_file() {
+        parse(make_cfg_file("colorscheme_file: /non/exists.yml").path);
+    }
+
+    #[test]
+    fn test_parse_window() {
+        let config = parse(
+            make_cfg_file(
+                r#"
+window:
+  columns: 100
+  lines: 40
+  maximized: true
+  position: { x: 10, y: 20 }
+"#,
+            )
+            .path,
+        );
+        assert_eq!(config.window.size(), Some((100, 40)));
+        assert!(config.window.maximized);
+        assert!(!config.window.fullscreen);
+        assert_eq!(config.window.position, Some(Position { x: 10, y: 20 }));
+    }
+
+    #[test]
+    fn test_parse_window_appearance() {
+        let config = parse(
+            make_cfg_file("window:\n  decorations: false\n  padding: 5\n  opacity: 0.9").path,
+        );
+        assert_eq!(config.window.decorations, Some(false));
+        assert_eq!(config.window.padding, Some(Padding { x: 5, y: 5 }));
+        assert_eq!(config.window.opacity, Some(0.9));
+
+        let config = parse(make_cfg_file("window:\n  padding: { x: 5, y: 10 }").path);
+        assert_eq!(config.window.padding, Some(Padding { x: 5, y: 10 }));
+    }
+
+    #[test]
+    #[should_panic(expected = "window.opacity must be between 0.0 and 1.0")]
+    fn test_parse_invalid_opacity() {
+        parse(make_cfg_file("window:\n  opacity: 1.5").path);
+    }
+
+    #[test]
+    #[should_panic(expected = "window.columns and window.lines must be set together")]
+    fn test_parse_window_columns_without_lines() {
+        parse(make_cfg_file("window:\n  columns: 100").path);
+    }
+
+    #[test]
+    fn test_parse_cursor() {
+        let config = parse(
+            make_cfg_file(
+                r##"
+colors:
+  cursor: "#000000"
+cursor:
+  shape: beam
+  blink: true
+  blink_interval: 500
+  color: "#ffffff"
+"##,
+            )
+            .path,
+        );
+        assert_eq!(config.cursor.shape, Some(CursorShape::Beam));
+        assert_eq!(config.cursor.blink, Some(true));
+        assert_eq!(config.cursor.blink_interval, Some(500));
+        assert_eq!(config.colors.cursor.unwrap().to_string(), "#ffffff");
+    }
+
+    #[test]
+    fn test_parse_custom_backend() {
+        let config = parse(
+            make_cfg_file(
+                r#"
+backend: custom
+custom_backend:
+  args: ["xfce4-terminal", "-x", "{nvim}", "{nvim_args}"]
+"#,
+            )
+            .path,
+        );
+        assert_eq!(config.backend, Some(Backend::Custom));
+        assert_eq!(
+            config.custom_backend.unwrap().args,
+            vec!["xfce4-terminal", "-x", "{nvim}", "{nvim_args}"]
+        );
+    }
+
+    #[test]
+    #[should_panic(expected = "custom backend requires custom_backend.args")]
+    fn test_parse_custom_backend_without_args() {
+        parse(make_cfg_file("backend: custom").path);
+    }
+
     #[test]
     #[should_panic(
-        expected = "font_size: invalid type: string \"sadfa\", expected u8 at line 1 column 12"
+        expected = "font_size: invalid type: string \"sadfa\", expected a font size in points, e.g. 12 or 11.5 at line 1 column 12"
     )]
     fn test_parse_invalid_font_size() {
         parse(make_cfg_file("font_size: sadfa").path);
     }
+
+    #[test]
+    #[should_panic(
+        expected = "font_size: invalid font size -3, expected a non-negative number (0 = unset) at line 1 column 12"
+    )]
+    fn test_parse_negative_font_size() {
+        parse(make_cfg_file("font_size: -3").path);
+    }
+
+    #[test]
+    fn test_load_layers() {
+        let system = make_cfg_file(
+            r#"
+fonts: [system_font]
+font_size: 12
+window:
+  columns: 100
+  lines: 40
+  maximized: true
+"#,
+        );
+        let user = make_cfg_file(
+            r#"
+font_size: 14
+window:
+  opacity: 0.9
+  maximized: false
+"#,
+        );
+        let config = load(&[system.path, user.path], None, &[]);
+        assert_eq!(config.font_families(), vec!["system_font"]);
+        assert_eq!(config.font_size, 14.0);
+        assert_eq!(config.window.size(), Some((100, 40)));
+        assert_eq!(config.window.opacity, Some(0.9));
+        assert!(!config.window.maximized);
+    }
+
+    #[test]
+    fn test_load_project_file() {
+        let user = make_cfg_file("nvim_exe_path: /usr/bin/nvim\nfont_size: 14");
+        let project = make_cfg_file("nvim_exe_path: ./evil\nfont_size: 16");
+        let config = load(&[user.path], Some(&project.path), &[]);
+        assert_eq!(config.nvim_exe_path, "/usr/bin/nvim");
+        assert_eq!(config.font_size, 16.0);
+    }
+
+    #[test]
+    fn test_load_project_file_denied_keys() {
+        let user = make_cfg_file("backend: kitty");
+        let project = make_cfg_file(
+            "backend: foot\ncolorscheme_file: /etc/passwd\ngnome_terminal_profile: true\nfont_size: 16",
+        );
+        let config = load(&[user.path], Some(&project.path), &[]);
+        assert_eq!(config.backend, Some(Backend::Kitty));
+        assert_eq!(config.colorscheme_file, None);
+        assert!(!config.gnome_terminal_profile);
+        assert_eq!(config.font_size, 16.0);
+    }
+
+    #[test]
+    #[should_panic(expected = "must not contain quotes, backslashes or control characters")]
+    fn test_parse_font_with_quote() {
+        parse(make_cfg_file("fonts:\n  - 'Hack\", }) os.execute(\"touch x\") --'").path);
+    }
+
+    #[test]
+    #[should_panic(expected = "font: \"-liga\\n\" must not contain")]
+    fn test_parse_font_feature_with_newline() {
+        parse(make_cfg_file("font:\n  features: [\"-liga\\n\"]").path);
+    }
+
+    #[test]
+    fn test_load_invalid_project_file() {
+        let user = make_cfg_file("font_size: 14");
+        let project = make_cfg_file("font_size: [16]");
+        let config = load(&[user.path], Some(&project.path), &[]);
+        assert_eq!(config.font_size, 14.0);
+    }
+
+    #[test]
+    #[should_panic(expected = "glrnvim.yaml: font_size: invalid type")]
+    fn test_parse_invalid_config_file() {
+        parse(make_cfg_file("font_size: [16]").path);
+    }
+
+    #[test]
+    #[should_panic(expected = "environment variable not found")]
+    fn test_parse_colorscheme_file_with_unknown_var() {
+        parse(make_cfg_file("colorscheme_file: $GLRNVIM_NON_EXISTS/theme.yml").path);
+    }
+
+    #[test]
+    fn test_find_project_file() {
+        let dir = tempdir().unwrap();
+        let sub_dir = dir.path().join("a").join("b");
+        std::fs::create_dir_all(&sub_dir).unwrap();
+        assert_eq!(find_project_file(&sub_dir), None);
+
+        let project_file = dir.path().join(PROJECT_CONFIG_NAME);
+        File::create(&project_file).unwrap();
+        assert_eq!(find_project_file(&sub_dir), Some(project_file));
+    }
+
+    #[test]
+    fn test_load_env_vars() {
+        let user = make_cfg_file("font_size: 14\nwindow: {columns: 100, lines: 40}");
+        let vars = [
+            ("GLRNVIM_FONT_SIZE", "15"),
+            ("GLRNVIM_WINDOW__OPACITY", "0.5"),
+            ("GLRNVIM_FONTS", "[Hack, Noto Color Emoji]"),
+            ("GLRNVIM_BACKEND", "kitty"),
+            ("FONT_SIZE", "20"),
+        ]
+        .map(|(k, v)| (k.to_string(), v.to_string()));
+        let config = load(&[user.path], None, &vars);
+        assert_eq!(config.font_size, 15.0);
+        assert_eq!(config.window.size(), Some((100, 40)));
+        assert_eq!(config.window.opacity, Some(0.5));
+        assert_eq!(config.font_families(), vec!["Hack", "Noto Color Emoji"]);
+        assert_eq!(config.backend, Some(Backend::Kitty));
+    }
+
+    #[test]
+    #[should_panic(expected = "environment variables: ")]
+    fn test_load_invalid_env_var() {
+        load(
+            &[],
+            None,
+            &[("GLRNVIM_LOAD_TERM_CONF".to_string(), "maybe".to_string())],
+        );
+    }
 }
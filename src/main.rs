@@ -3,15 +3,17 @@ extern crate env_logger;
 extern crate log;
 
 mod backend;
+mod colorscheme;
 mod config;
 mod error;
 
 use config::*;
 use std::env;
+use std::path::{Path, PathBuf};
 use std::process::{Command, Stdio};
 use sysinfo::Pid;
 
-const DEFAULT_FONT_SIZE: u8 = 12;
+const DEFAULT_FONT_SIZE: f64 = 12.0;
 
 const VERSION: &str = env!("CARGO_PKG_VERSION");
 
@@ -34,6 +36,19 @@ fn check_nvim(vim_exe_path: &str) {
     }
 }
 
+// The system wide config files, by the priority from low to high.
+fn system_config_files() -> Vec<PathBuf> {
+    let dirs = env::var("XDG_CONFIG_DIRS")
+        .ok()
+        .filter(|d| !d.is_empty())
+        .unwrap_or_else(|| "/etc/xdg".to_string());
+    dirs.split(':')
+        .rev()
+        .map(|d| Path::new(d).join("glrnvim").join("config.yml"))
+        .filter(|p| p.is_file())
+        .collect()
+}
+
 fn parse_args() -> (Config, Vec<String>) {
     let args: Vec<String> = env::args().collect();
     let mut n_args: Vec<String> = Vec::new();
@@ -53,32 +68,38 @@ fn parse_args() -> (Config, Vec<String>) {
         }
     }
 
-    let mut config: Config = match dirs::config_dir() {
-        Some(conf_dir) => {
-            let mut new_conf_path = conf_dir.clone();
-            new_conf_path.push("glrnvim");
-            new_conf_path.push("config.yml");
-
-            let mut old_conf_path = conf_dir;
-            old_conf_path.push("glrnvim.yml");
-
-            if new_conf_path.exists() {
-                log::debug!("Use config file: '{:?}'.", new_conf_path);
-                config::parse(new_conf_path)
-            } else if old_conf_path.exists() {
-                log::debug!("Use config file: '{:?}'.", old_conf_path);
-                config::parse(old_conf_path)
-            } else {
-                log::debug!("No config file found. Use default config.");
-                Config::default()
-            }
+    // The system config, the user config, the project config and the environment variables are
+    // merged in the order of priority from low to high.
+    let mut conf_files = system_config_files();
+    if let Some(conf_dir) = dirs::config_dir() {
+        let mut new_conf_path = conf_dir.clone();
+        new_conf_path.push("glrnvim");
+        new_conf_path.push("config.yml");
+
+        let mut old_conf_path = conf_dir;
+        old_conf_path.push("glrnvim.yml");
+
+        if new_conf_path.exists() {
+            conf_files.push(new_conf_path);
+        } else if old_conf_path.exists() {
+            conf_files.push(old_conf_path);
         }
-        None => Config::default(),
-    };
+    }
+    let project_file = env::current_dir()
+        .ok()
+        .and_then(|dir| config::find_project_file(&dir));
+    for path in conf_files.iter().chain(project_file.iter()) {
+        log::debug!("Use config file: '{:?}'.", path);
+    }
+    if conf_files.is_empty() && project_file.is_none() {
+        log::debug!("No config file found. Use default config.");
+    }
+    let vars: Vec<(String, String)> = env::vars().collect();
+    let mut config = config::load(&conf_files, project_file.as_deref(), &vars);
     config.fork = fork;
     if !config.load_term_conf {
         // Set our default configs if user doesn't use the terminal's conf.
-        if config.font_size == 0 {
+        if config.font_size == 0.0 {
             config.font_size = DEFAULT_FONT_SIZE;
         }
     }
@@ -136,6 +157,12 @@ fn show_help() {
             conf_dir.push("glrnvim");
             conf_dir.push("config.yml");
             println!("\nConfig file: {}", conf_dir.display());
+            println!(
+                "It is merged with $XDG_CONFIG_DIRS/glrnvim/config.yml, {} of the project and \
+                 the {}* environment variables.",
+                config::PROJECT_CONFIG_NAME,
+                config::ENV_PREFIX
+            );
             println!(
                 "See https://github.com/beeender/glrnvim/blob/master/glrnvim.yml for example."
             );
@@ -153,6 +180,7 @@ fn main() -> Result<(), Box<dyn std::error::Error>> {
 
     let mut backend_functions = backend::init(&config)?;
 
+    backend_functions.pre_start(&config);
     let mut command = backend_functions.create_command(&config);
 
     command.args(&n_args);